@@ -0,0 +1,39 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+/// Writes the C header for the functions in `src/capi.rs`.
+#[cfg(feature = "capi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some(String::from("BLS2BRS_H")),
+        autogen_warning: Some(String::from(
+            "/* Generated from src/capi.rs by build.rs, don't edit. */",
+        )),
+        cpp_compat: true,
+        export: cbindgen::ExportConfig {
+            rename: ["Status", "Options", "Buffer", "Report"]
+                .iter()
+                .map(|name| (name.to_string(), format!("Bls2brs{}", name)))
+                .collect(),
+            ..Default::default()
+        },
+        enumeration: cbindgen::EnumConfig {
+            prefix_with_name: true,
+            rename_variants: cbindgen::RenameRule::ScreamingSnakeCase,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/capi.rs")
+        .generate()
+        .expect("failed to generate C header")
+        .write_to_file("include/bls2brs.h");
+}
@@ -0,0 +1,185 @@
+#ifndef BLS2BRS_H
+#define BLS2BRS_H
+
+/* Generated from src/capi.rs by build.rs, don't edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum Bls2brsStatus {
+  BLS2BRS_STATUS_OK = 0,
+  /**
+   * A pointer was null or a string wasn't valid UTF-8.
+   */
+  BLS2BRS_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * A file couldn't be opened, created or written.
+   */
+  BLS2BRS_STATUS_IO = 2,
+  /**
+   * The input isn't a valid bls file, or a table isn't valid JSON.
+   */
+  BLS2BRS_STATUS_INVALID_DATA = 3,
+  /**
+   * The converter hit a bug. Please report it with the save.
+   */
+  BLS2BRS_STATUS_PANIC = 4,
+} Bls2brsStatus;
+
+/**
+ * The outcome of a conversion. Free with [`bls2brs_report_free`].
+ */
+typedef struct Bls2brsReport Bls2brsReport;
+
+/**
+ * Settings for a conversion. Zeroed options are the defaults.
+ */
+typedef struct Bls2brsOptions {
+  /**
+   * JSON object of Brickadia audio assets by Blockland music loop, or null.
+   */
+  const char *music_table_json;
+  /**
+   * When the save was made, in milliseconds since the Unix epoch, or 0 for now.
+   */
+  int64_t save_time_ms;
+  /**
+   * JSON object of Brickadia vehicle prefabs by Blockland vehicle, or null.
+   */
+  const char *vehicle_table_json;
+} Bls2brsOptions;
+
+/**
+ * Bytes owned by the library. Free with [`bls2brs_buffer_free`].
+ */
+typedef struct Bls2brsBuffer {
+  uint8_t *data;
+  uintptr_t len;
+} Bls2brsBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Converts the contents of a bls file in memory.
+ *
+ * On success, `out_brs` holds the brs file and `out_report` the report.
+ * `options` may be null.
+ *
+ * # Safety
+ *
+ * `bls` must point to `bls_len` readable bytes, and the other pointers must
+ * be valid or null as described.
+ */
+enum Bls2brsStatus bls2brs_convert_buffer(const uint8_t *bls,
+                                          uintptr_t bls_len,
+                                          const struct Bls2brsOptions *options,
+                                          struct Bls2brsBuffer *out_brs,
+                                          struct Bls2brsReport **out_report);
+
+/**
+ * Converts a bls file and writes the brs file to `output_path`.
+ *
+ * On success, `out_report` holds the report. `options` may be null.
+ *
+ * # Safety
+ *
+ * The paths must be null-terminated strings, and the other pointers must be
+ * valid or null as described.
+ */
+enum Bls2brsStatus bls2brs_convert_file(const char *input_path,
+                                        const char *output_path,
+                                        const struct Bls2brsOptions *options,
+                                        struct Bls2brsReport **out_report);
+
+/**
+ * Describes the last failure on this thread. Valid until the next call.
+ */
+const char *bls2brs_last_error(void);
+
+/**
+ * # Safety
+ *
+ * `buffer` must come from [`bls2brs_convert_buffer`] and not be freed twice.
+ */
+void bls2brs_buffer_free(struct Bls2brsBuffer buffer);
+
+/**
+ * # Safety
+ *
+ * `report` must come from a conversion and not be freed twice. It may be null.
+ */
+void bls2brs_report_free(struct Bls2brsReport *report);
+
+/**
+ * How many Blockland bricks were converted.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+uintptr_t bls2brs_report_count_success(const struct Bls2brsReport *report);
+
+/**
+ * How many Blockland bricks failed to convert.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+uintptr_t bls2brs_report_count_failure(const struct Bls2brsReport *report);
+
+/**
+ * How many Brickadia bricks the save holds.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+uintptr_t bls2brs_report_count_output(const struct Bls2brsReport *report);
+
+/**
+ * How many different ui_names were unknown.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+uintptr_t bls2brs_report_unknown_count(const struct Bls2brsReport *report);
+
+/**
+ * The unknown ui_name at `index`, most common first, or null if out of range.
+ * Valid until the report is freed.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+const char *bls2brs_report_unknown_name(const struct Bls2brsReport *report, uintptr_t index);
+
+/**
+ * How many bricks had the unknown ui_name at `index`, or 0 if out of range.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+uintptr_t bls2brs_report_unknown_bricks(const struct Bls2brsReport *report, uintptr_t index);
+
+/**
+ * The whole report as JSON, including the sidecar. Valid until the report is freed.
+ *
+ * # Safety
+ *
+ * `report` must be a valid report.
+ */
+const char *bls2brs_report_json(const struct Bls2brsReport *report);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BLS2BRS_H */
@@ -0,0 +1,140 @@
+use crate::{convert_with_options, ConvertOptions, ConvertReport};
+use std::{
+    collections::HashMap,
+    io::{self, prelude::*, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// Totals for every save converted out of one archive.
+#[derive(Debug, Default)]
+pub struct ArchiveReport {
+    pub unknown_ui_names: HashMap<String, usize>,
+    pub count_saves: usize,
+    pub count_success: usize,
+    pub count_failure: usize,
+    pub count_output: usize,
+    /// `.bls` entries that couldn't be read or converted, with why.
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+/// Converts every `.bls` entry in a zip archive.
+///
+/// `on_save` is called with the entry path and its report as soon as each save
+/// is converted, so only one converted save is held in memory at a time.
+/// Entries with unsafe paths (absolute or escaping the archive) are skipped.
+/// Entries that fail to read or convert are listed in the report and the rest
+/// are still converted; only errors from `on_save` stop the conversion.
+pub fn convert_archive(
+    reader: impl Read + Seek,
+    options: &ConvertOptions,
+    mut on_save: impl FnMut(&Path, ConvertReport) -> io::Result<()>,
+) -> io::Result<ArchiveReport> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut report = ArchiveReport::default();
+
+    for index in 0..archive.len() {
+        // Look at the name first, so broken entries that aren't saves are ignored
+        let path = match archive.by_index_raw(index) {
+            Ok(entry) => match entry.enclosed_name() {
+                Some(path) if !entry.is_dir() && is_bls(path) => path.to_owned(),
+                _ => continue,
+            },
+            Err(e) => {
+                report
+                    .failures
+                    .push((PathBuf::from(format!("entry {}", index)), e.to_string()));
+                continue;
+            }
+        };
+
+        let converted = archive
+            .by_index(index)
+            .map_err(io::Error::from)
+            .and_then(|entry| bl_save::Reader::new(BufReader::new(entry)))
+            .and_then(|reader| convert_with_options(reader, options));
+        let converted = match converted {
+            Ok(converted) => converted,
+            Err(e) => {
+                report.failures.push((path, e.to_string()));
+                continue;
+            }
+        };
+
+        for (ui_name, count) in &converted.unknown_ui_names {
+            *report.unknown_ui_names.entry(ui_name.clone()).or_default() += count;
+        }
+
+        report.count_saves += 1;
+        report.count_success += converted.count_success;
+        report.count_failure += converted.count_failure;
+        report.count_output += converted.write_data.bricks.len();
+
+        on_save(&path, converted)?;
+    }
+
+    Ok(report)
+}
+
+fn is_bls(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("bls"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::bls;
+    use std::io::Cursor;
+    use zip::write::{FileOptions, ZipWriter};
+
+    fn archive(entries: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        let mut reader = writer.finish().unwrap();
+        reader.set_position(0);
+        reader
+    }
+
+    #[test]
+    fn converts_saves_and_reports_bad_ones() {
+        let save = bls(&[r#"2x4" 0 0 0.3 0 0 3  0 0 1 1 1"#]);
+        let broken = bls(&["a brick line without a name"]);
+        let zip = archive(&[
+            ("a/One.BLS", &save),
+            ("Broken.bls", &broken),
+            ("readme.txt", b"hello"),
+            ("two.bls", &save),
+        ]);
+
+        let mut saved = vec![];
+        let report = convert_archive(zip, &ConvertOptions::default(), |path, converted| {
+            saved.push((path.to_owned(), converted.write_data.bricks.len()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            saved,
+            vec![(PathBuf::from("a/One.BLS"), 1), (PathBuf::from("two.bls"), 1)]
+        );
+        assert_eq!(report.count_saves, 2);
+        assert_eq!(report.count_success, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, PathBuf::from("Broken.bls"));
+    }
+
+    #[test]
+    fn stops_when_saving_fails() {
+        let save = bls(&[r#"2x4" 0 0 0.3 0 0 3  0 0 1 1 1"#]);
+        let zip = archive(&[("one.bls", &save), ("two.bls", &save)]);
+
+        let result = convert_archive(zip, &ConvertOptions::default(), |_, _| {
+            Err(io::Error::other("disk full"))
+        });
+        assert!(result.is_err());
+    }
+}
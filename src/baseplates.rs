@@ -0,0 +1,183 @@
+//! Baseplates, the large flat bricks builds stand on. They can be built from
+//! a different asset than other plates, split into chunks Brickadia accepts,
+//! and listed together as the ground layer.
+
+use crate::{
+    limits::{split_desc, MAX_BRICK_EXTENT},
+    types::BrickDesc,
+};
+
+/// Which Brickadia brick baseplates are built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseplateAsset {
+    /// A plate with studs, like the Blockland brick.
+    Studded,
+    /// A smooth tile without seams or studs.
+    SmoothTile,
+}
+
+impl BaseplateAsset {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "studded" => Some(BaseplateAsset::Studded),
+            "smooth" => Some(BaseplateAsset::SmoothTile),
+            _ => None,
+        }
+    }
+
+    fn asset(self) -> &'static str {
+        match self {
+            BaseplateAsset::Studded => "PB_DefaultBrick",
+            BaseplateAsset::SmoothTile => "PB_DefaultSmoothTile",
+        }
+    }
+}
+
+/// How baseplates are converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseplateOptions {
+    pub asset: BaseplateAsset,
+    /// Largest half extent of a baseplate chunk, in Brickadia units.
+    /// Larger baseplates are split into a grid of chunks. `None` keeps them whole.
+    pub max_extent: Option<u32>,
+    /// List baseplates in the sidecar, so they can be raised or lowered together.
+    pub ground_layer: bool,
+}
+
+impl Default for BaseplateOptions {
+    fn default() -> Self {
+        Self {
+            asset: BaseplateAsset::Studded,
+            max_extent: Some(MAX_BRICK_EXTENT),
+            ground_layer: false,
+        }
+    }
+}
+
+/// Plates planted on the ground need at least this half extent, 8 studs, along
+/// both sides to count as baseplates.
+const MIN_PLANTED_EXTENT: u32 = 40;
+
+/// Whether a brick of a mapping is a baseplate. Mappings mark named baseplates,
+/// and large plain plates the save marks as planted on the ground count too.
+pub(crate) fn is_baseplate(from: &bl_save::Brick, desc: &BrickDesc) -> bool {
+    desc.baseplate
+        || (from.base.is_baseplate
+            && desc.asset == "PB_DefaultBrick"
+            && desc.size.2 == 2
+            && desc.size.0.min(desc.size.1) >= MIN_PLANTED_EXTENT
+            && !desc.modter
+            && !desc.non_priority)
+}
+
+/// Builds a baseplate from the chosen asset, split into chunks if it's too large.
+/// Chunks are kept within `limit` too, the largest half extent of any brick.
+pub(crate) fn baseplate_descs(
+    desc: BrickDesc,
+    options: &BaseplateOptions,
+    limit: u32,
+) -> Vec<BrickDesc> {
+    let mut desc = desc.baseplate();
+    desc.asset = options.asset.asset();
+
+    let max_extent = match options.max_extent {
+        Some(max_extent) => max_extent.min(limit),
+        None => return vec![desc],
+    };
+
+    split_desc(desc, max_extent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    fn options(baseplates: BaseplateOptions) -> ConvertOptions {
+        ConvertOptions {
+            baseplates,
+            ..Default::default()
+        }
+    }
+
+    fn assets(report: &crate::ConvertReport) -> Vec<&str> {
+        let data = &report.write_data;
+        data.bricks
+            .iter()
+            .map(|brick| data.brick_assets[brick.asset_name_index as usize].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn named_baseplates_are_split_into_chunks() {
+        let report = convert(
+            &["64x64 Base\" 0 0 0.1 0 1 3  0 0 1 1 1"],
+            &options(BaseplateOptions {
+                asset: BaseplateAsset::SmoothTile,
+                max_extent: Some(160),
+                ground_layer: true,
+            }),
+        );
+
+        assert_eq!(assets(&report), ["PB_DefaultSmoothTile"; 4]);
+        assert!(report.write_data.bricks.iter().all(|brick| brick.size == (160, 160, 2)));
+        assert_eq!(report.ground_layer, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn whole_baseplates_stay_in_one_piece() {
+        let report = convert(
+            &["64x64 Base\" 0 0 0.1 0 1 3  0 0 1 1 1"],
+            &options(BaseplateOptions {
+                max_extent: None,
+                ..Default::default()
+            }),
+        );
+        assert_eq!(report.write_data.bricks.len(), 1);
+        assert_eq!(report.write_data.bricks[0].size, (320, 320, 2));
+    }
+
+    #[test]
+    fn large_plates_on_the_ground_are_baseplates() {
+        let report = convert(
+            &["16x32F\" 0 0 0.1 0 1 3  0 0 1 1 1"],
+            &options(BaseplateOptions {
+                asset: BaseplateAsset::SmoothTile,
+                max_extent: Some(80),
+                ground_layer: true,
+            }),
+        );
+
+        assert_eq!(assets(&report), ["PB_DefaultSmoothTile"; 2]);
+        assert!(report.write_data.bricks.iter().all(|brick| brick.size == (80, 80, 2)));
+        assert_eq!(report.ground_layer, [0, 1]);
+    }
+
+    #[test]
+    fn other_bricks_are_not_baseplates() {
+        let report = convert(
+            &[
+                // Not planted on the ground
+                "16x32F\" 0 0 0.1 0 0 3  0 0 1 1 1",
+                // Too small
+                "1x2F\" 0 0 0.1 0 1 3  0 0 1 1 1",
+                // Not a plate
+                "8x8\" 0 0 0.3 0 1 3  0 0 1 1 1",
+            ],
+            &options(BaseplateOptions {
+                asset: BaseplateAsset::SmoothTile,
+                max_extent: Some(40),
+                ground_layer: true,
+            }),
+        );
+        assert_eq!(assets(&report), ["PB_DefaultBrick"; 3]);
+        assert!(report.ground_layer.is_empty());
+    }
+
+    #[test]
+    fn parses_asset_names() {
+        assert_eq!(BaseplateAsset::parse("studded"), Some(BaseplateAsset::Studded));
+        assert_eq!(BaseplateAsset::parse("smooth"), Some(BaseplateAsset::SmoothTile));
+        assert_eq!(BaseplateAsset::parse("Smooth"), None);
+    }
+}
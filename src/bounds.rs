@@ -0,0 +1,164 @@
+use brs::Direction::*;
+
+/// Half extents of the fixed size brick assets the mappings use.
+/// Procedural assets carry their own size, these don't.
+const STATIC_ASSET_SIZES: &[(&str, (u32, u32, u32))] = &[
+    ("B_1x1_Cone", (5, 5, 6)),
+    ("B_1x1_Round", (5, 5, 6)),
+    ("B_1x1F_Octo", (5, 5, 2)),
+    ("B_1x1F_Round", (5, 5, 2)),
+    ("B_1x1F_Speaker", (5, 5, 2)),
+    ("B_1x1f_Tile_Corner", (5, 5, 2)),
+    ("B_1x_Octo", (5, 5, 5)),
+    ("B_2x2_Cone", (10, 10, 12)),
+    ("B_2x2_Corner", (10, 10, 6)),
+    ("B_2x2_Round", (10, 10, 6)),
+    ("B_2x2F_Octo", (10, 10, 2)),
+    ("B_2x2F_Round", (10, 10, 2)),
+    ("B_2x_Octo", (10, 10, 10)),
+    ("B_2x_Octo_90Deg", (10, 10, 10)),
+    ("B_2x_Octo_Cone", (10, 10, 10)),
+    ("B_2x_Octo_T", (10, 10, 10)),
+    ("B_8x8_Lattice_Plate", (40, 40, 2)),
+    ("B_Bush", (10, 10, 14)),
+    ("B_Gravestone", (5, 10, 12)),
+    ("B_Picket_Fence", (5, 20, 12)),
+    ("B_Pine_Tree", (20, 20, 36)),
+    ("B_Pumpkin", (10, 10, 9)),
+];
+
+/// Used for fixed size assets missing from the table above.
+const FALLBACK_SIZE: (u32, u32, u32) = (5, 5, 6);
+
+/// An axis-aligned box in Brickadia units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: (i32, i32, i32),
+    pub max: (i32, i32, i32),
+}
+
+impl Bounds {
+    /// The bounds of a brick whose asset is `asset`.
+    pub fn of_brick(asset: &str, brick: &brs::Brick) -> Self {
+        let (x, y, z) = world_half_extents(asset, brick);
+        let (x, y, z) = (x as i32, y as i32, z as i32);
+        let (px, py, pz) = brick.position;
+        Self {
+            min: (px - x, py - y, pz - z),
+            max: (px + x, py + y, pz + z),
+        }
+    }
+
+    /// Whether the two boxes share volume. Touching faces don't count.
+    pub fn overlaps(&self, other: &Bounds) -> bool {
+        self.min.0 < other.max.0
+            && other.min.0 < self.max.0
+            && self.min.1 < other.max.1
+            && other.min.1 < self.max.1
+            && self.min.2 < other.max.2
+            && other.min.2 < self.max.2
+    }
+
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds {
+            min: (
+                self.min.0.min(other.min.0),
+                self.min.1.min(other.min.1),
+                self.min.2.min(other.min.2),
+            ),
+            max: (
+                self.max.0.max(other.max.0),
+                self.max.1.max(other.max.1),
+                self.max.2.max(other.max.2),
+            ),
+        }
+    }
+
+    pub fn center(&self) -> (i32, i32, i32) {
+        (
+            (self.min.0 + self.max.0) / 2,
+            (self.min.1 + self.max.1) / 2,
+            (self.min.2 + self.max.2) / 2,
+        )
+    }
+}
+
+/// The half extents of an asset before orientation is applied.
+pub fn asset_half_extents(asset: &str, size: (u32, u32, u32)) -> (u32, u32, u32) {
+    if size != (0, 0, 0) {
+        return size;
+    }
+
+    STATIC_ASSET_SIZES
+        .iter()
+        .find(|(name, _)| *name == asset)
+        .map(|(_, size)| *size)
+        .unwrap_or(FALLBACK_SIZE)
+}
+
+/// The half extents of a brick along the world X, Y and Z axes.
+///
+/// The direction picks which world axis the brick's Z points along,
+/// and odd rotations swap the two remaining axes.
+pub fn world_half_extents(asset: &str, brick: &brs::Brick) -> (u32, u32, u32) {
+    let (x, y, z) = asset_half_extents(asset, brick.size);
+    let (x, y) = match brick.rotation {
+        brs::Rotation::Deg90 | brs::Rotation::Deg270 => (y, x),
+        _ => (x, y),
+    };
+
+    match brick.direction {
+        XPositive | XNegative => (z, y, x),
+        YPositive | YNegative => (y, z, x),
+        ZPositive | ZNegative => (x, y, z),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brick(
+        size: (u32, u32, u32),
+        direction: brs::Direction,
+        rotation: brs::Rotation,
+    ) -> brs::Brick {
+        brs::Brick {
+            asset_name_index: 0,
+            size,
+            position: (100, 0, 10),
+            direction,
+            rotation,
+            collision: true,
+            visibility: true,
+            material_index: 0,
+            color: brs::ColorMode::Set(0),
+            owner_index: 0,
+        }
+    }
+
+    #[test]
+    fn turns_extents_with_the_brick() {
+        let upright = brick((10, 20, 2), ZPositive, brs::Rotation::Deg90);
+        assert_eq!(world_half_extents("PB_DefaultBrick", &upright), (20, 10, 2));
+
+        let sideways = brick((10, 20, 2), XPositive, brs::Rotation::Deg0);
+        assert_eq!(world_half_extents("PB_DefaultBrick", &sideways), (2, 20, 10));
+
+        let cone = brick((0, 0, 0), ZPositive, brs::Rotation::Deg0);
+        assert_eq!(world_half_extents("B_2x2_Cone", &cone), (10, 10, 12));
+    }
+
+    #[test]
+    fn touching_boxes_do_not_overlap() {
+        let a = brick((10, 10, 6), ZPositive, brs::Rotation::Deg0);
+        let a = Bounds::of_brick("PB_DefaultBrick", &a);
+        let mut b = a;
+        b.min.0 += 20;
+        b.max.0 += 20;
+        assert!(!a.overlaps(&b));
+        b.min.0 -= 1;
+        assert!(a.overlaps(&b));
+        assert_eq!(a.union(&b).center(), (110, 0, 10));
+    }
+}
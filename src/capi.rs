@@ -0,0 +1,412 @@
+//! A C API for embedding the converter, built with the `capi` feature.
+//! `build.rs` generates `include/bls2brs.h` from this file.
+//!
+//! Functions return a [`Status`]. When it's not `Ok`, [`bls2brs_last_error`]
+//! describes what went wrong.
+
+use crate::{convert_with_options, ConvertOptions, ConvertReport};
+use brs::chrono::{TimeZone, Utc};
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString},
+    fs::File,
+    io::{self, prelude::*, BufReader, BufWriter, ErrorKind},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// A pointer was null or a string wasn't valid UTF-8.
+    InvalidArgument = 1,
+    /// A file couldn't be opened, created or written.
+    Io = 2,
+    /// The input isn't a valid bls file, or a table isn't valid JSON.
+    InvalidData = 3,
+    /// The converter hit a bug. Please report it with the save.
+    Panic = 4,
+}
+
+/// Settings for a conversion. Zeroed options are the defaults.
+#[repr(C)]
+pub struct Options {
+    /// JSON object of Brickadia audio assets by Blockland music loop, or null.
+    pub music_table_json: *const c_char,
+    /// When the save was made, in milliseconds since the Unix epoch, or 0 for now.
+    pub save_time_ms: i64,
+    /// JSON object of Brickadia vehicle prefabs by Blockland vehicle, or null.
+    pub vehicle_table_json: *const c_char,
+}
+
+/// Bytes owned by the library. Free with [`bls2brs_buffer_free`].
+#[repr(C)]
+pub struct Buffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+/// The outcome of a conversion. Free with [`bls2brs_report_free`].
+pub struct Report {
+    count_success: usize,
+    count_failure: usize,
+    count_output: usize,
+    /// Unknown ui_names with their brick counts, most common first.
+    unknown_ui_names: Vec<(CString, usize)>,
+    json: CString,
+}
+
+impl Report {
+    fn new(converted: &ConvertReport) -> Self {
+        let mut unknown_ui_names: Vec<_> = converted
+            .unknown_ui_names
+            .iter()
+            .map(|(ui_name, count)| (c_string(ui_name), *count))
+            .collect();
+        unknown_ui_names.sort_by(|(an, ac), (bn, bc)| bc.cmp(ac).then(an.cmp(bn)));
+
+        Self {
+            count_success: converted.count_success,
+            count_failure: converted.count_failure,
+            count_output: converted.write_data.bricks.len(),
+            unknown_ui_names,
+            json: c_string(&converted.to_json().to_string()),
+        }
+    }
+}
+
+/// Converts the contents of a bls file in memory.
+///
+/// On success, `out_brs` holds the brs file and `out_report` the report.
+/// `options` may be null.
+///
+/// # Safety
+///
+/// `bls` must point to `bls_len` readable bytes, and the other pointers must
+/// be valid or null as described.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_convert_buffer(
+    bls: *const u8,
+    bls_len: usize,
+    options: *const Options,
+    out_brs: *mut Buffer,
+    out_report: *mut *mut Report,
+) -> Status {
+    catch_panic(|| {
+        if bls.is_null() || out_brs.is_null() || out_report.is_null() {
+            return fail(Status::InvalidArgument, "null pointer");
+        }
+
+        let result = read_options(options).and_then(|options| {
+            let bls = slice::from_raw_parts(bls, bls_len);
+            let converted = bl_save::Reader::new(bls)
+                .and_then(|reader| convert_with_options(reader, &options))
+                .map_err(io_failure)?;
+
+            let mut brs = Vec::new();
+            brs::write_save(&mut brs, &converted.write_data).map_err(io_failure)?;
+            Ok((brs, Report::new(&converted)))
+        });
+
+        match result {
+            Ok((brs, report)) => {
+                let brs = brs.into_boxed_slice();
+                let len = brs.len();
+                *out_brs = Buffer {
+                    data: Box::into_raw(brs) as *mut u8,
+                    len,
+                };
+                *out_report = Box::into_raw(Box::new(report));
+                Status::Ok
+            }
+            Err((status, message)) => fail(status, &message),
+        }
+    })
+}
+
+/// Converts a bls file and writes the brs file to `output_path`.
+///
+/// On success, `out_report` holds the report. `options` may be null.
+///
+/// # Safety
+///
+/// The paths must be null-terminated strings, and the other pointers must be
+/// valid or null as described.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_convert_file(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    options: *const Options,
+    out_report: *mut *mut Report,
+) -> Status {
+    catch_panic(|| {
+        if out_report.is_null() {
+            return fail(Status::InvalidArgument, "null pointer");
+        }
+
+        let result = (|| {
+            let input_path = read_str(input_path)?;
+            let output_path = read_str(output_path)?;
+            let options = read_options(options)?;
+
+            let input_file = File::open(input_path).map_err(io_failure)?;
+            let converted = bl_save::Reader::new(BufReader::new(input_file))
+                .and_then(|reader| convert_with_options(reader, &options))
+                .map_err(io_failure)?;
+
+            let output_file = File::create(output_path).map_err(io_failure)?;
+            let mut output_file = BufWriter::new(output_file);
+            brs::write_save(&mut output_file, &converted.write_data)
+                .and_then(|_| output_file.flush())
+                .map_err(io_failure)?;
+
+            Ok(Report::new(&converted))
+        })();
+
+        match result {
+            Ok(report) => {
+                *out_report = Box::into_raw(Box::new(report));
+                Status::Ok
+            }
+            Err((status, message)) => fail(status, &message),
+        }
+    })
+}
+
+/// Describes the last failure on this thread. Valid until the next call.
+#[no_mangle]
+pub extern "C" fn bls2brs_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+/// # Safety
+///
+/// `buffer` must come from [`bls2brs_convert_buffer`] and not be freed twice.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_buffer_free(buffer: Buffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+/// # Safety
+///
+/// `report` must come from a conversion and not be freed twice. It may be null.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_free(report: *mut Report) {
+    if !report.is_null() {
+        drop(Box::from_raw(report));
+    }
+}
+
+/// How many Blockland bricks were converted.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_count_success(report: *const Report) -> usize {
+    (*report).count_success
+}
+
+/// How many Blockland bricks failed to convert.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_count_failure(report: *const Report) -> usize {
+    (*report).count_failure
+}
+
+/// How many Brickadia bricks the save holds.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_count_output(report: *const Report) -> usize {
+    (*report).count_output
+}
+
+/// How many different ui_names were unknown.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_unknown_count(report: *const Report) -> usize {
+    (*report).unknown_ui_names.len()
+}
+
+/// The unknown ui_name at `index`, most common first, or null if out of range.
+/// Valid until the report is freed.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_unknown_name(
+    report: *const Report,
+    index: usize,
+) -> *const c_char {
+    match (&*report).unknown_ui_names.get(index) {
+        Some((ui_name, _)) => ui_name.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// How many bricks had the unknown ui_name at `index`, or 0 if out of range.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_unknown_bricks(
+    report: *const Report,
+    index: usize,
+) -> usize {
+    match (&*report).unknown_ui_names.get(index) {
+        Some((_, count)) => *count,
+        None => 0,
+    }
+}
+
+/// The whole report as JSON, including the sidecar. Valid until the report is freed.
+///
+/// # Safety
+///
+/// `report` must be a valid report.
+#[no_mangle]
+pub unsafe extern "C" fn bls2brs_report_json(report: *const Report) -> *const c_char {
+    (*report).json.as_ptr()
+}
+
+type Failure = (Status, String);
+
+fn fail(status: Status, message: &str) -> Status {
+    LAST_ERROR.with(|e| *e.borrow_mut() = c_string(message));
+    status
+}
+
+/// Runs a conversion, turning a panic into [`Status::Panic`] instead of
+/// unwinding into the caller.
+fn catch_panic(convert: impl FnOnce() -> Status) -> Status {
+    panic::catch_unwind(AssertUnwindSafe(convert)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown error");
+        fail(Status::Panic, &format!("The converter panicked: {}", message))
+    })
+}
+
+fn io_failure(error: io::Error) -> Failure {
+    let status = match error.kind() {
+        ErrorKind::InvalidData | ErrorKind::UnexpectedEof => Status::InvalidData,
+        _ => Status::Io,
+    };
+    (status, error.to_string())
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err((Status::InvalidArgument, String::from("null pointer")));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| (Status::InvalidArgument, e.to_string()))
+}
+
+unsafe fn read_options(options: *const Options) -> Result<ConvertOptions, Failure> {
+    let mut parsed = ConvertOptions::default();
+
+    let options = match options.as_ref() {
+        Some(options) => options,
+        None => return Ok(parsed),
+    };
+
+    if !options.music_table_json.is_null() {
+        let music = read_str(options.music_table_json)?;
+        parsed.music = serde_json::from_str(music)
+            .map_err(|e| (Status::InvalidData, format!("Invalid music table: {}", e)))?;
+    }
+
+    if !options.vehicle_table_json.is_null() {
+        let vehicles = read_str(options.vehicle_table_json)?;
+        parsed.vehicles = serde_json::from_str(vehicles)
+            .map_err(|e| (Status::InvalidData, format!("Invalid vehicle table: {}", e)))?;
+    }
+
+    if options.save_time_ms != 0 {
+        parsed.save_time = Utc.timestamp_millis_opt(options.save_time_ms).single();
+    }
+
+    Ok(parsed)
+}
+
+/// Strings with interior null bytes are cut off there.
+fn c_string(s: &str) -> CString {
+    let end = s.find('\0').unwrap_or(s.len());
+    CString::new(&s[..end]).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(bls2brs_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn converts_a_buffer() {
+        let bls = crate::test_util::bls(&["2x4\" 0 0 0.3 0 0 3  0 0 1 1 1"]);
+        let mut brs = Buffer {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+        let mut report = ptr::null_mut();
+        unsafe {
+            let status =
+                bls2brs_convert_buffer(bls.as_ptr(), bls.len(), ptr::null(), &mut brs, &mut report);
+            assert_eq!(status, Status::Ok);
+            assert_eq!(bls2brs_report_count_output(report), 1);
+            assert!(brs.len > 0);
+            bls2brs_buffer_free(brs);
+            bls2brs_report_free(report);
+        }
+    }
+
+    #[test]
+    fn reports_null_pointers() {
+        let status = unsafe {
+            bls2brs_convert_file(ptr::null(), ptr::null(), ptr::null(), ptr::null_mut())
+        };
+        assert_eq!(status, Status::InvalidArgument);
+        assert_eq!(last_error(), "null pointer");
+    }
+
+    #[test]
+    fn turns_panics_into_a_status() {
+        let status = catch_panic(|| panic!("bad brick"));
+        assert_eq!(status, Status::Panic);
+        assert_eq!(last_error(), "The converter panicked: bad brick");
+
+        let status = catch_panic(|| panic!("bad brick {}", 7));
+        assert_eq!(status, Status::Panic);
+        assert_eq!(last_error(), "The converter panicked: bad brick 7");
+    }
+}
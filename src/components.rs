@@ -0,0 +1,260 @@
+//! Behavior attached to converted bricks.
+//!
+//! `brs` writes version 4 saves, which have no brick components, so components
+//! are collected in the [`ConvertReport`] and written to a JSON sidecar file
+//! next to the save instead.
+
+use crate::{
+    doors::Door,
+    events::Interaction,
+    spawns::{SpawnKind, SpawnVehicle},
+    ConvertReport,
+};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bumped whenever the sidecar layout changes.
+const SIDECAR_VERSION: u64 = 2;
+/// Audio emitters go on the speaker of music bricks.
+const SPEAKER_ASSET: &str = "B_1x1F_Speaker";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Component {
+    Interaction(Interaction),
+    /// Players, vehicles or respawning players appear at the brick.
+    Spawn {
+        kind: SpawnKind,
+        /// The vehicle that appears, for vehicle spawns.
+        vehicle: Option<SpawnVehicle>,
+    },
+    /// Plays the Brickadia audio asset chosen for a Blockland music loop.
+    AudioEmitter { music: String, asset: String },
+    /// Opens and closes the brick, starting in the state it was saved in.
+    Door(Door),
+}
+
+impl Component {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Component::Interaction(interaction) => {
+                let mut value = interaction.to_json();
+                value["type"] = "interaction".into();
+                value
+            }
+            Component::Spawn { kind, vehicle } => {
+                let mut value = json!({ "type": "spawn", "kind": kind.name() });
+                if let Some(vehicle) = vehicle {
+                    value["vehicle"] = vehicle.to_json();
+                }
+                value
+            }
+            Component::AudioEmitter { music, asset } => json!({
+                "type": "audio_emitter",
+                "music": music,
+                "asset": asset,
+            }),
+            Component::Door(door) => {
+                let mut value = door.to_json();
+                value["type"] = "door".into();
+                value
+            }
+        }
+    }
+
+    /// The asset of the part of a converted brick this component belongs on, if it matters.
+    fn preferred_asset(&self) -> Option<&'static str> {
+        match self {
+            Component::AudioEmitter { .. } => Some(SPEAKER_ASSET),
+            _ => None,
+        }
+    }
+}
+
+/// An event of a converted brick that has no Brickadia counterpart.
+#[derive(Debug, Clone)]
+pub struct UntranslatedEvent {
+    /// Index of the Blockland brick.
+    pub source: usize,
+    pub event: String,
+}
+
+/// Assigns components gathered per Blockland brick to the first brick it
+/// converted to, or the first with the component's preferred asset.
+pub(crate) fn attach_components(
+    data: &brs::WriteData,
+    brick_sources: &[usize],
+    pending: Vec<(usize, Component)>,
+) -> Vec<(usize, Component)> {
+    let mut bricks_of_source: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, source) in brick_sources.iter().enumerate() {
+        bricks_of_source.entry(*source).or_default().push(index);
+    }
+
+    let mut components: Vec<_> = pending
+        .into_iter()
+        .filter_map(|(source, component)| {
+            let bricks = bricks_of_source.get(&source)?;
+            let preferred = component.preferred_asset().and_then(|asset| {
+                bricks.iter().copied().find(|&index| {
+                    let asset_index = data.bricks[index].asset_name_index as usize;
+                    data.brick_assets[asset_index] == asset
+                })
+            });
+            Some((preferred.unwrap_or(bricks[0]), component))
+        })
+        .collect();
+    components.sort_by_key(|(index, _)| *index);
+    components
+}
+
+/// Everything a conversion produced that the brs file can't hold,
+/// with bricks keyed by their index in the save.
+pub fn sidecar_json(report: &ConvertReport) -> Value {
+    let mut bricks: BTreeMap<usize, serde_json::Map<String, Value>> = BTreeMap::new();
+
+    for (index, name) in &report.brick_names {
+        bricks
+            .entry(*index)
+            .or_default()
+            .insert(String::from("name"), name.as_str().into());
+    }
+
+    for (index, component) in &report.components {
+        let components = bricks
+            .entry(*index)
+            .or_default()
+            .entry("components")
+            .or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(components) = components {
+            components.push(component.to_json());
+        }
+    }
+
+    let bricks: serde_json::Map<_, _> = bricks
+        .into_iter()
+        .map(|(index, brick)| (index.to_string(), Value::Object(brick)))
+        .collect();
+
+    let untranslated_events: Vec<_> = report
+        .untranslated_events
+        .iter()
+        .map(|e| {
+            json!({
+                "source": e.source,
+                "ui_name": report.ui_names.get(e.source),
+                "event": e.event,
+            })
+        })
+        .collect();
+
+    let spawns: Vec<_> = report.spawns.iter().map(|s| s.to_json()).collect();
+    let volumes: Vec<_> = report.volumes.iter().map(|v| v.to_json()).collect();
+
+    json!({
+        "version": SIDECAR_VERSION,
+        "bricks": bricks,
+        "spawns": spawns,
+        "volumes": volumes,
+        "ground_layer": report.ground_layer,
+        "untranslated_events": untranslated_events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{Action, Trigger},
+        test_util::convert,
+        ConvertOptions,
+    };
+
+    fn message(text: &str) -> Component {
+        Component::Interaction(Interaction {
+            trigger: Trigger::Interact,
+            delay: 0,
+            target_name: None,
+            action: Action::Message(text.to_string()),
+        })
+    }
+
+    #[test]
+    fn components_go_on_the_first_converted_brick() {
+        let options = ConvertOptions::default();
+        let data = convert(&["2x4\" 0 0 0.3 0 0 3  0 0 1 1 1"], &options).write_data;
+        let pending = vec![(2, message("b")), (1, message("a")), (5, message("lost"))];
+        let components = attach_components(&data, &[0, 1, 1, 2], pending);
+        assert_eq!(components, [(1, message("a")), (3, message("b"))]);
+    }
+
+    #[test]
+    fn sidecar_keys_bricks_by_index() {
+        let report = convert(
+            &[
+                "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "2x4\" 4 0 0.3 0 0 3  0 0 1 1 1",
+                "+-EVENT\t0\t1\tonActivate\t0\tClient\t\tcenterPrint\tHello",
+                "+-EVENT\t1\t1\tonActivate\t0\tSelf\t\tfireRelay",
+                "+-NTOBJECTNAME _lever",
+            ],
+            &ConvertOptions::default(),
+        );
+        let sidecar = sidecar_json(&report);
+
+        assert_eq!(sidecar["version"], SIDECAR_VERSION);
+        let (index, _) = report.components[0];
+        let brick = &sidecar["bricks"][index.to_string()];
+        assert_eq!(brick["name"], "lever");
+        assert_eq!(brick["components"][0]["type"], "interaction");
+        assert!(sidecar["bricks"].get("0").is_none());
+        assert_eq!(
+            sidecar["untranslated_events"],
+            json!([{ "source": 1, "ui_name": "2x4", "event": "onActivate -> Self -> fireRelay" }])
+        );
+    }
+
+    #[test]
+    fn sidecar_lists_spawns() {
+        let report = convert(
+            &[
+                "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "Spawn Point\" 4 0 0.3 0 0 3  0 0 1 1 1",
+                "+-NTOBJECTNAME _start",
+            ],
+            &ConvertOptions::default(),
+        );
+        let sidecar = sidecar_json(&report);
+
+        let (index, _) = report.components[0];
+        let brick = &sidecar["bricks"][index.to_string()];
+        assert_eq!(brick["name"], "start");
+        assert_eq!(brick["components"], json!([{ "type": "spawn", "kind": "player" }]));
+        assert_eq!(sidecar["spawns"].as_array().unwrap().len(), 1);
+        assert!(sidecar["bricks"].get("0").is_none());
+    }
+
+    #[test]
+    fn audio_emitters_go_on_the_speaker() {
+        let options = ConvertOptions {
+            music: [("musicData_Drone".to_string(), "BA_AMB_Drone".to_string())].into(),
+            ..Default::default()
+        };
+        let report = convert(
+            &[
+                "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "Music Brick\" 4 0 0.3 0 0 3  0 0 1 1 1",
+                "+-AUDIOEMITTER musicData_Drone\"",
+            ],
+            &options,
+        );
+
+        assert_eq!(report.components.len(), 1);
+        let (index, component) = &report.components[0];
+        let brick = &report.write_data.bricks[*index];
+        assert_eq!(report.write_data.brick_assets[brick.asset_name_index as usize], SPEAKER_ASSET);
+        assert_eq!(
+            component.to_json(),
+            json!({ "type": "audio_emitter", "music": "musicData_Drone", "asset": "BA_AMB_Drone" })
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{resolve_color, ConvertReport};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+    io::{self, ErrorKind},
+};
+
+/// Bumped whenever the manifest layout changes.
+const MANIFEST_VERSION: u64 = 1;
+/// How many changed brick indices to list per ui_name and change in text output.
+const MAX_LISTED_SOURCES: usize = 10;
+
+/// One converted brick and the Blockland brick it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrickRecord {
+    pub source: usize,
+    pub asset: String,
+    pub size: (u32, u32, u32),
+    pub position: (i32, i32, i32),
+    pub direction: brs::Direction,
+    pub rotation: brs::Rotation,
+    pub color: brs::Color,
+}
+
+/// What a conversion made of every Blockland brick.
+/// Two manifests of the same save can be compared with [`diff`].
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    /// The ui_name of every Blockland brick, by index.
+    pub ui_names: Vec<String>,
+    pub bricks: Vec<BrickRecord>,
+}
+
+impl Manifest {
+    pub fn from_report(report: &ConvertReport) -> Self {
+        let data = &report.write_data;
+        let bricks = data
+            .bricks
+            .iter()
+            .zip(&report.brick_sources)
+            .map(|(brick, &source)| BrickRecord {
+                source,
+                asset: data.brick_assets[brick.asset_name_index as usize].clone(),
+                size: brick.size,
+                position: brick.position,
+                direction: brick.direction,
+                rotation: brick.rotation,
+                color: resolve_color(data, brick.color),
+            })
+            .collect();
+
+        Self {
+            ui_names: report.ui_names.clone(),
+            bricks,
+        }
+    }
+
+    /// Describes the bricks of a saved brs file, taking the Blockland brick
+    /// each came from out of the manifest written when it was converted.
+    /// Fails if the file holds a different number of bricks than the manifest.
+    pub fn from_brs(data: &brs::WriteData, converted: &Manifest) -> io::Result<Self> {
+        if data.bricks.len() != converted.bricks.len() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The save has {} bricks but its manifest has {}",
+                    data.bricks.len(),
+                    converted.bricks.len(),
+                ),
+            ));
+        }
+
+        let bricks = data
+            .bricks
+            .iter()
+            .zip(&converted.bricks)
+            .map(|(brick, record)| BrickRecord {
+                source: record.source,
+                asset: data
+                    .brick_assets
+                    .get(brick.asset_name_index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                size: brick.size,
+                position: brick.position,
+                direction: brick.direction,
+                rotation: brick.rotation,
+                color: resolve_color(data, brick.color),
+            })
+            .collect();
+
+        Ok(Self {
+            ui_names: converted.ui_names.clone(),
+            bricks,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let bricks: Vec<_> = self
+            .bricks
+            .iter()
+            .map(|b| {
+                json!({
+                    "source": b.source,
+                    "asset": b.asset,
+                    "size": b.size,
+                    "position": b.position,
+                    "direction": u8::from(b.direction),
+                    "rotation": u8::from(b.rotation),
+                    "color": format_color(b.color),
+                })
+            })
+            .collect();
+
+        json!({
+            "version": MANIFEST_VERSION,
+            "ui_names": self.ui_names,
+            "bricks": bricks,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> io::Result<Self> {
+        let invalid = |what: &str| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid manifest: {}", what),
+            )
+        };
+
+        if value["version"].as_u64() != Some(MANIFEST_VERSION) {
+            return Err(invalid("unsupported version"));
+        }
+
+        let ui_names = value["ui_names"]
+            .as_array()
+            .ok_or_else(|| invalid("missing ui_names"))?
+            .iter()
+            .map(|v| v.as_str().map(String::from))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("ui_names must be strings"))?;
+
+        let bricks = value["bricks"]
+            .as_array()
+            .ok_or_else(|| invalid("missing bricks"))?
+            .iter()
+            .map(|b| {
+                Some(BrickRecord {
+                    source: b["source"].as_u64()? as usize,
+                    asset: b["asset"].as_str()?.to_string(),
+                    size: triple(&b["size"], Value::as_u64)?,
+                    position: triple(&b["position"], Value::as_i64)?,
+                    direction: brs::Direction::try_from(b["direction"].as_u64()? as u8).ok()?,
+                    rotation: brs::Rotation::try_from(b["rotation"].as_u64()? as u8).ok()?,
+                    color: parse_color(b["color"].as_str()?)?,
+                })
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("malformed brick"))?;
+
+        Ok(Self { ui_names, bricks })
+    }
+}
+
+fn triple<T, U: TryFrom<T>>(value: &Value, get: impl Fn(&Value) -> Option<T>) -> Option<(U, U, U)> {
+    match value.as_array()?.as_slice() {
+        [x, y, z] => Some((
+            U::try_from(get(x)?).ok()?,
+            U::try_from(get(y)?).ok()?,
+            U::try_from(get(z)?).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+/// Writes a color as `#rrggbbaa`.
+fn format_color(color: brs::Color) -> String {
+    let rgba = u32::from_be_bytes([color.r(), color.g(), color.b(), color.a()]);
+    format!("#{:08x}", rgba)
+}
+
+/// Parses a color written as `#rrggbbaa`.
+fn parse_color(s: &str) -> Option<brs::Color> {
+    let rgba = u32::from_str_radix(s.strip_prefix('#')?, 16).ok()?;
+    Some(brs::Color::from_rgba(
+        (rgba >> 24) as u8,
+        (rgba >> 16) as u8,
+        (rgba >> 8) as u8,
+        rgba as u8,
+    ))
+}
+
+/// A way in which the output of a Blockland brick changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Change {
+    /// The brick converts now but didn't before.
+    Added,
+    /// The brick converted before but doesn't now.
+    Removed,
+    /// The brick maps to different assets, orientations or a different number of bricks.
+    Replaced,
+    Moved,
+    Resized,
+    Recolored,
+}
+
+impl Change {
+    pub fn name(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Replaced => "replaced",
+            Change::Moved => "moved",
+            Change::Resized => "resized",
+            Change::Recolored => "recolored",
+        }
+    }
+}
+
+/// How the output of one Blockland brick changed.
+#[derive(Debug, Clone)]
+pub struct BrickDiff {
+    pub source: usize,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    /// Changed Blockland bricks, grouped by ui_name.
+    pub by_ui_name: BTreeMap<String, Vec<BrickDiff>>,
+}
+
+/// Compares two conversions of the same save, matching output bricks by the
+/// Blockland brick they came from.
+///
+/// Bricks converted from the same Blockland brick are compared in order, so
+/// moving or resizing one part of a multi-brick mapping is reported as such.
+pub fn diff(old: &Manifest, new: &Manifest) -> DiffReport {
+    let old_by_source = group_by_source(&old.bricks);
+    let new_by_source = group_by_source(&new.bricks);
+    let no_bricks = Vec::new();
+
+    let mut report = DiffReport::default();
+
+    for source in 0..old.ui_names.len().max(new.ui_names.len()) {
+        let old_bricks = old_by_source.get(&source).unwrap_or(&no_bricks);
+        let new_bricks = new_by_source.get(&source).unwrap_or(&no_bricks);
+
+        let mut changes = match (old_bricks.is_empty(), new_bricks.is_empty()) {
+            (true, true) => continue,
+            (true, false) => vec![Change::Added],
+            (false, true) => vec![Change::Removed],
+            _ => compare(old_bricks, new_bricks),
+        };
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        changes.sort_unstable();
+        changes.dedup();
+
+        let ui_name = new
+            .ui_names
+            .get(source)
+            .or_else(|| old.ui_names.get(source))
+            .cloned()
+            .unwrap_or_default();
+
+        report
+            .by_ui_name
+            .entry(ui_name)
+            .or_default()
+            .push(BrickDiff { source, changes });
+    }
+
+    report
+}
+
+fn group_by_source(bricks: &[BrickRecord]) -> BTreeMap<usize, Vec<&BrickRecord>> {
+    let mut groups: BTreeMap<usize, Vec<&BrickRecord>> = BTreeMap::new();
+    for brick in bricks {
+        groups.entry(brick.source).or_default().push(brick);
+    }
+    groups
+}
+
+fn compare(old: &[&BrickRecord], new: &[&BrickRecord]) -> Vec<Change> {
+    let same_shape = old.len() == new.len()
+        && old.iter().zip(new).all(|(a, b)| {
+            a.asset == b.asset && a.direction == b.direction && a.rotation == b.rotation
+        });
+
+    if !same_shape {
+        return vec![Change::Replaced];
+    }
+
+    let mut changes = vec![];
+    for (a, b) in old.iter().zip(new) {
+        if a.position != b.position {
+            changes.push(Change::Moved);
+        }
+        if a.size != b.size {
+            changes.push(Change::Resized);
+        }
+        if a.color != b.color {
+            changes.push(Change::Recolored);
+        }
+    }
+    changes
+}
+
+impl DiffReport {
+    pub fn is_empty(&self) -> bool {
+        self.by_ui_name.is_empty()
+    }
+
+    /// How many Blockland bricks had each kind of change.
+    pub fn totals(&self) -> BTreeMap<Change, usize> {
+        let mut totals = BTreeMap::new();
+        for change in self.by_ui_name.values().flatten().flat_map(|d| &d.changes) {
+            *totals.entry(*change).or_default() += 1;
+        }
+        totals
+    }
+
+    pub fn to_json(&self) -> Value {
+        let totals: serde_json::Map<_, _> = self
+            .totals()
+            .into_iter()
+            .map(|(change, count)| (change.name().to_string(), count.into()))
+            .collect();
+
+        let by_ui_name: serde_json::Map<_, _> = self
+            .by_ui_name
+            .iter()
+            .map(|(ui_name, diffs)| {
+                let diffs: Vec<_> = diffs
+                    .iter()
+                    .map(|d| {
+                        let changes: Vec<_> = d.changes.iter().map(|c| c.name()).collect();
+                        json!({ "source": d.source, "changes": changes })
+                    })
+                    .collect();
+                (ui_name.clone(), diffs.into())
+            })
+            .collect();
+
+        json!({
+            "totals": totals,
+            "ui_names": by_ui_name,
+        })
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        for (ui_name, diffs) in &self.by_ui_name {
+            writeln!(f, "{} ({} bricks changed):", ui_name, diffs.len())?;
+
+            let mut by_change: BTreeMap<Change, Vec<usize>> = BTreeMap::new();
+            for d in diffs {
+                for change in &d.changes {
+                    by_change.entry(*change).or_default().push(d.source);
+                }
+            }
+
+            for (change, sources) in by_change {
+                let listed: Vec<_> = sources
+                    .iter()
+                    .take(MAX_LISTED_SOURCES)
+                    .map(usize::to_string)
+                    .collect();
+                write!(f, "  {:<10} {}", change.name(), listed.join(", "))?;
+                if sources.len() > MAX_LISTED_SOURCES {
+                    write!(f, " and {} more", sources.len() - MAX_LISTED_SOURCES)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Total:")?;
+        for (change, count) in self.totals() {
+            writeln!(f, "  {:<10} {:>6} bricks", change.name(), count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::convert;
+
+    fn manifest(lines: &[&str]) -> Manifest {
+        Manifest::from_report(&convert(lines, &Default::default()))
+    }
+
+    #[test]
+    fn writes_colors_as_rrggbbaa() {
+        let color = brs::Color::from_rgba(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(format_color(color), "#12345678");
+        assert_eq!(parse_color("#12345678"), Some(color));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let old = manifest(&[
+            "2x4\" 0 0 0.3 1 0 3  0 0 1 1 1",
+            "1x1 Cone\" 1 1 0.6 2 0 63  0 0 1 1 1",
+        ]);
+        let new = Manifest::from_json(&old.to_json()).unwrap();
+        assert_eq!(new.ui_names, old.ui_names);
+        assert_eq!(new.bricks, old.bricks);
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn reports_changes_by_ui_name() {
+        let old = manifest(&[
+            "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+            "2x4\" 2 0 0.3 0 0 3  0 0 1 1 1",
+            "Weird Brick\" 4 0 0.3 0 0 3  0 0 1 1 1",
+        ]);
+        let mut new = old.clone();
+        new.bricks[0].position.0 += 10;
+        new.bricks[0].color = brs::Color::from_rgba(0, 0, 0, 255);
+        new.bricks[1].asset = String::from("PB_DefaultTile");
+        new.bricks.push(BrickRecord {
+            source: 2,
+            ..new.bricks[1].clone()
+        });
+
+        let report = diff(&old, &new);
+        let changes: Vec<_> = report.by_ui_name["2x4"]
+            .iter()
+            .map(|d| (d.source, d.changes.clone()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (0, vec![Change::Moved, Change::Recolored]),
+                (1, vec![Change::Replaced])
+            ]
+        );
+        assert_eq!(report.by_ui_name["Weird Brick"][0].changes, [Change::Added]);
+        assert_eq!(report.totals()[&Change::Moved], 1);
+        assert_eq!(diff(&new, &old).by_ui_name["Weird Brick"][0].changes, [Change::Removed]);
+    }
+
+    #[test]
+    fn reads_saved_bricks_with_their_manifest() {
+        let report = convert(&["2x4\" 0 0 0.3 0 0 3  0 0 1 1 1"], &Default::default());
+        let converted = Manifest::from_report(&report);
+
+        let mut data = report.write_data;
+        data.bricks[0].position.2 += 4;
+        let saved = Manifest::from_brs(&data, &converted).unwrap();
+        assert_eq!(saved.bricks[0].source, 0);
+        assert_eq!(diff(&converted, &saved).totals()[&Change::Moved], 1);
+
+        data.bricks.push(data.bricks[0].clone());
+        assert!(Manifest::from_brs(&data, &converted).is_err());
+    }
+}
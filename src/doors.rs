@@ -0,0 +1,147 @@
+//! Door bricks, which Blockland saves as a different brick for each state.
+
+use crate::types::BrickMapping;
+use serde_json::{json, Value};
+
+/// Half the width of a door panel, where it's hinged.
+const HINGE: i32 = 18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorStyle {
+    Plain,
+    House,
+    Glass,
+    Jail,
+    Castle,
+    BathroomStall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorState {
+    Closed,
+    OpenClockwise,
+    OpenCounterClockwise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Door {
+    pub style: DoorStyle,
+    pub state: DoorState,
+}
+
+impl DoorStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            DoorStyle::Plain => "plain",
+            DoorStyle::House => "house",
+            DoorStyle::Glass => "glass",
+            DoorStyle::Jail => "jail",
+            DoorStyle::Castle => "castle",
+            DoorStyle::BathroomStall => "bathroom_stall",
+        }
+    }
+}
+
+impl DoorState {
+    pub fn is_open(self) -> bool {
+        self != DoorState::Closed
+    }
+}
+
+/// Reads the door a brick is by its ui_name. Blockland saves a door as the
+/// datablock of its state, so whether it was left open comes from the ui_name
+/// too, like `Jail Door Open CCW`.
+///
+/// Only the doors of the base game are known. Door add-ons name and build
+/// their doors differently, and fall through to the other mappings.
+pub fn parse_door(ui_name: &str) -> Option<Door> {
+    let (name, state) = if let Some(name) = ui_name.strip_suffix(" Open CCW") {
+        (name, DoorState::OpenCounterClockwise)
+    } else if let Some(name) = ui_name.strip_suffix(" Open CW") {
+        (name, DoorState::OpenClockwise)
+    } else {
+        (ui_name, DoorState::Closed)
+    };
+
+    let style = match name {
+        "Plain Door" => DoorStyle::Plain,
+        "House Door" => DoorStyle::House,
+        "Glass Door" => DoorStyle::Glass,
+        "Jail Door" => DoorStyle::Jail,
+        "Castle Door" => DoorStyle::Castle,
+        "Bathroom Stall Door" => DoorStyle::BathroomStall,
+        _ => return None,
+    };
+
+    Some(Door { style, state })
+}
+
+/// Swings the parts of a closed door panel around its hinge at one edge,
+/// when the door was saved open.
+pub(crate) fn swing(panel: BrickMapping, state: DoorState) -> BrickMapping {
+    let sign = match state {
+        DoorState::Closed => return panel,
+        DoorState::OpenClockwise => 1,
+        DoorState::OpenCounterClockwise => -1,
+    };
+
+    panel
+        .into_iter()
+        .map(|mut desc| {
+            let (x, y, z) = desc.offset;
+            let (a, b, c) = desc.size;
+            desc.offset = (sign * (y + HINGE), -HINGE - sign * x, z);
+            desc.size = (b, a, c);
+            desc
+        })
+        .collect()
+}
+
+impl Door {
+    pub fn to_json(&self) -> Value {
+        let swing = match self.state {
+            DoorState::Closed => None,
+            DoorState::OpenClockwise => Some("cw"),
+            DoorState::OpenCounterClockwise => Some("ccw"),
+        };
+
+        json!({
+            "style": self.style.name(),
+            "open": self.state.is_open(),
+            "swing": swing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BrickDesc;
+
+    #[test]
+    fn reads_the_state_from_the_ui_name() {
+        let closed = parse_door("Jail Door").unwrap();
+        assert_eq!(closed.style, DoorStyle::Jail);
+        assert_eq!(closed.state, DoorState::Closed);
+
+        let open = parse_door("Bathroom Stall Door Open CCW").unwrap();
+        assert_eq!(open.style, DoorStyle::BathroomStall);
+        assert_eq!(open.state, DoorState::OpenCounterClockwise);
+
+        assert_eq!(parse_door("Trap Door"), None);
+    }
+
+    #[test]
+    fn swings_panels_around_the_hinge() {
+        let panel = vec![BrickDesc::new("PB_DefaultMicroBrick")
+            .size((18, 1, 34))
+            .offset((0, 0, 0))];
+        assert_eq!(swing(panel.clone(), DoorState::Closed)[0].offset, (0, 0, 0));
+
+        let clockwise = &swing(panel.clone(), DoorState::OpenClockwise)[0];
+        assert_eq!((clockwise.offset, clockwise.size), ((18, -18, 0), (1, 18, 34)));
+
+        let counter = &swing(panel, DoorState::OpenCounterClockwise)[0];
+        assert_eq!(counter.offset, (-18, -18, 0));
+    }
+}
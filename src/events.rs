@@ -0,0 +1,333 @@
+//! Parsing of Blockland events and their translation into interactions.
+
+use crate::{
+    extra::{self, object_name, split_extra},
+    map_position,
+};
+use serde_json::{json, Value};
+
+/// Target of events that act on the brick itself.
+const TARGET_SELF: &str = "Self";
+/// Target of events that act on a named brick given by `target_name`.
+const TARGET_NAMED: &str = "-1";
+
+/// One `+-EVENT` line of a brick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub enabled: bool,
+    /// The input event, like `onActivate`.
+    pub input: String,
+    /// Delay in milliseconds.
+    pub delay: u32,
+    /// Who the output event acts on, like `Self`, `Player` or `Client`.
+    pub target: String,
+    /// The brick name the event targets, if `target` is `-1`.
+    pub target_name: String,
+    /// The output event, like `setColor`.
+    pub output: String,
+    pub params: Vec<String>,
+}
+
+/// Parses the value of an `EVENT` extra line. The fields are separated by tabs:
+/// index, enabled, input, delay, target, target name, output and parameters.
+pub fn parse_event(value: &str) -> Option<Event> {
+    let mut fields = value.split('\t');
+    let _index = fields.next()?;
+    let enabled = fields.next()?.trim() != "0";
+    let input = fields.next()?.to_string();
+    let delay = fields.next()?.trim().parse().ok()?;
+    let target = fields.next()?.to_string();
+    let target_name = fields.next()?.to_string();
+    let output = fields.next()?.to_string();
+
+    let mut params: Vec<_> = fields.map(String::from).collect();
+    while params.last().is_some_and(|p| p.is_empty()) {
+        params.pop();
+    }
+
+    Some(Event {
+        enabled,
+        input,
+        delay,
+        target,
+        target_name,
+        output,
+        params,
+    })
+}
+
+/// What makes an interaction happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// A player interacts with the brick.
+    Interact,
+    /// A player touches the brick.
+    Touch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorAction {
+    Toggle,
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Shows a message to the player.
+    Message(String),
+    SetColor(brs::Color),
+    /// Moves the player to a named brick.
+    TeleportToBrick(String),
+    /// Moves the player to a position in Brickadia units.
+    TeleportToPosition((i32, i32, i32)),
+    Door(DoorAction),
+}
+
+/// A translated event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub trigger: Trigger,
+    /// Delay in milliseconds.
+    pub delay: u32,
+    /// The named brick the action applies to, or `None` for the brick itself.
+    pub target_name: Option<String>,
+    pub action: Action,
+}
+
+/// Translates an event into an interaction, if it's one of the supported ones.
+/// `colors` is the color table of the save, for `setColor`.
+pub fn translate_event(event: &Event, colors: &[brs::Color]) -> Option<Interaction> {
+    let trigger = match event.input.as_str() {
+        "onActivate" => Trigger::Interact,
+        "onPlayerTouch" => Trigger::Touch,
+        _ => return None,
+    };
+
+    let param = |index: usize| event.params.get(index).map(String::as_str).unwrap_or("");
+
+    let (acts_on_brick, action) = match event.output.to_ascii_lowercase().as_str() {
+        "centerprint" | "bottomprint" | "chatmessage" => {
+            (false, Action::Message(param(0).to_string()))
+        }
+        "setcolor" => {
+            let color = colors.get(param(0).trim().parse::<usize>().ok()?)?;
+            (true, Action::SetColor(*color))
+        }
+        "teleport" | "teletobrick" => {
+            let destination = param(0).trim();
+            let action = match parse_position(destination) {
+                Some(position) => Action::TeleportToPosition(map_position(position)),
+                None if !destination.is_empty() => {
+                    Action::TeleportToBrick(object_name(destination))
+                }
+                None => return None,
+            };
+            (false, action)
+        }
+        "door" => {
+            let door_action = match param(0).to_ascii_lowercase().as_str() {
+                "toggle" | "" => DoorAction::Toggle,
+                "open" | "open cw" | "open ccw" | "opencw" | "openccw" => DoorAction::Open,
+                "close" => DoorAction::Close,
+                _ => return None,
+            };
+            (true, Action::Door(door_action))
+        }
+        "dooropen" => (true, Action::Door(DoorAction::Open)),
+        "doorclose" => (true, Action::Door(DoorAction::Close)),
+        "doortoggle" => (true, Action::Door(DoorAction::Toggle)),
+        _ => return None,
+    };
+
+    let target_name = match event.target.as_str() {
+        TARGET_SELF if acts_on_brick => None,
+        TARGET_NAMED if acts_on_brick => {
+            Some(object_name(&event.target_name))
+        }
+        "Player" | "Client" if !acts_on_brick => None,
+        _ => return None,
+    };
+
+    Some(Interaction {
+        trigger,
+        delay: event.delay,
+        target_name,
+        action,
+    })
+}
+
+/// Translates the enabled events of a brick. Also returns descriptions of the
+/// events that couldn't be translated.
+pub(crate) fn brick_interactions(
+    brick: &bl_save::Brick,
+    colors: &[brs::Color],
+) -> (Vec<Interaction>, Vec<String>) {
+    let mut interactions = vec![];
+    let mut untranslated = vec![];
+
+    for (tag, value) in brick
+        .unknown_extra
+        .iter()
+        .filter_map(|line| split_extra(line))
+    {
+        if tag != extra::EVENT {
+            continue;
+        }
+
+        match parse_event(value) {
+            Some(event) if !event.enabled => {}
+            Some(event) => match translate_event(&event, colors) {
+                Some(interaction) => interactions.push(interaction),
+                None => untranslated.push(event.summary()),
+            },
+            None => untranslated.push(value.replace('\t', " ")),
+        }
+    }
+
+    (interactions, untranslated)
+}
+
+/// Parses a position like `1 2 3` in Blockland units.
+fn parse_position(s: &str) -> Option<(f32, f32, f32)> {
+    let mut parts = s.split_whitespace().map(|p| p.parse().ok());
+    let position = (parts.next()??, parts.next()??, parts.next()??);
+    match parts.next() {
+        None => Some(position),
+        Some(_) => None,
+    }
+}
+
+impl Event {
+    /// Describes the event like `onActivate -> Self -> setColor 3`.
+    pub fn summary(&self) -> String {
+        let target = if self.target == TARGET_NAMED {
+            &self.target_name
+        } else {
+            &self.target
+        };
+        let mut summary = format!("{} -> {} -> {}", self.input, target, self.output);
+        for param in &self.params {
+            summary.push(' ');
+            summary.push_str(param);
+        }
+        summary
+    }
+}
+
+impl Interaction {
+    pub fn to_json(&self) -> Value {
+        let trigger = match self.trigger {
+            Trigger::Interact => "interact",
+            Trigger::Touch => "touch",
+        };
+
+        let action = match &self.action {
+            Action::Message(text) => json!({ "type": "message", "text": text }),
+            Action::SetColor(color) => {
+                json!({ "type": "set_color", "color": format!("{:?}", color) })
+            }
+            Action::TeleportToBrick(name) => json!({ "type": "teleport", "brick_name": name }),
+            Action::TeleportToPosition(position) => {
+                json!({ "type": "teleport", "position": position })
+            }
+            Action::Door(door_action) => {
+                let door_action = match door_action {
+                    DoorAction::Toggle => "toggle",
+                    DoorAction::Open => "open",
+                    DoorAction::Close => "close",
+                };
+                json!({ "type": "door", "action": door_action })
+            }
+        };
+
+        json!({
+            "trigger": trigger,
+            "delay_ms": self.delay,
+            "target_name": self.target_name,
+            "action": action,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::brick;
+
+    fn event(value: &str) -> Event {
+        parse_event(value).unwrap()
+    }
+
+    #[test]
+    fn parses_event_fields() {
+        let parsed = event("0\t1\tonActivate\t250\t-1\t_lift\tsetColor\t4\t\t");
+        assert_eq!(
+            parsed,
+            Event {
+                enabled: true,
+                input: String::from("onActivate"),
+                delay: 250,
+                target: String::from("-1"),
+                target_name: String::from("_lift"),
+                output: String::from("setColor"),
+                params: vec![String::from("4")],
+            }
+        );
+        assert_eq!(parsed.summary(), "onActivate -> _lift -> setColor 4");
+        assert!(parse_event("0\t1\tonActivate\tsoon").is_none());
+    }
+
+    #[test]
+    fn translates_supported_events() {
+        let colors = [brs::Color::from_rgba(1, 2, 3, 255)];
+
+        let teleport = event("0\t1\tonPlayerTouch\t0\tPlayer\t\tteleport\t1 2 3");
+        let touch = translate_event(&teleport, &colors);
+        assert_eq!(
+            touch,
+            Some(Interaction {
+                trigger: Trigger::Touch,
+                delay: 0,
+                target_name: None,
+                action: Action::TeleportToPosition((40, 20, 60)),
+            })
+        );
+
+        let color = translate_event(&event("0\t1\tonActivate\t0\t-1\t_wall\tsetColor\t0"), &colors);
+        assert_eq!(color.unwrap().target_name.as_deref(), Some("wall"));
+
+        let door = translate_event(&event("0\t1\tonActivate\t0\tSelf\t\tdoor\tOpen CCW"), &colors);
+        assert_eq!(door.unwrap().action, Action::Door(DoorAction::Open));
+    }
+
+    #[test]
+    fn leaves_out_unsupported_events() {
+        let colors = [];
+        for value in [
+            "0\t1\tonBotTouch\t0\tSelf\t\tsetColor\t0",
+            "0\t1\tonActivate\t0\tSelf\t\tsetColor\t9",
+            "0\t1\tonActivate\t0\tSelf\t\tcenterPrint\thi",
+            "0\t1\tonActivate\t0\tPlayer\t\tdoor\ttoggle",
+            "0\t1\tonActivate\t0\tSelf\t\tfireRelay",
+        ] {
+            assert_eq!(translate_event(&event(value), &colors), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn collects_interactions_of_a_brick() {
+        let brick = brick(&[
+            "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+            "+-EVENT\t0\t1\tonActivate\t0\tClient\t\tcenterPrint\tHello",
+            "+-EVENT\t1\t0\tonActivate\t0\tClient\t\tcenterPrint\tOff",
+            "+-EVENT\t2\t1\tonActivate\t0\tSelf\t\tfireRelay",
+            "+-EVENT\tbroken",
+            "+-OWNER 1",
+        ]);
+        let (interactions, untranslated) = brick_interactions(&brick, &[]);
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0].action, Action::Message(String::from("Hello")));
+        assert_eq!(untranslated, ["onActivate -> Self -> fireRelay", "broken"]);
+    }
+}
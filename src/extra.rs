@@ -0,0 +1,77 @@
+//! Helpers for the `+-TAG value` lines that follow a brick in a bls file,
+//! which `bl_save` exposes as `unknown_extra`.
+
+pub const OWNER: &str = "OWNER";
+pub const EVENT: &str = "EVENT";
+pub const LIGHT: &str = "LIGHT";
+pub const EMITTER: &str = "EMITTER";
+pub const ITEM: &str = "ITEM";
+pub const AUDIO_EMITTER: &str = "AUDIOEMITTER";
+pub const VEHICLE: &str = "VEHICLE";
+pub const OBJECT_NAME: &str = "NTOBJECTNAME";
+
+/// Splits an extra line like `+-OWNER 1234` into its tag and value.
+/// The value is separated by a space, or a tab for events.
+pub fn split_extra(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_prefix("+-")?;
+    match line.find([' ', '\t']) {
+        Some(index) => Some((&line[..index], &line[index + 1..])),
+        None => Some((line, "")),
+    }
+}
+
+/// Finds the value of the first extra line with the given tag.
+pub fn find_extra<'a>(brick: &'a bl_save::Brick, tag: &str) -> Option<&'a str> {
+    brick
+        .unknown_extra
+        .iter()
+        .filter_map(|line| split_extra(line))
+        .find(|(t, _)| *t == tag)
+        .map(|(_, value)| value)
+}
+
+/// Splits a value like `JeepVehicle" 1` into the quoted datablock name
+/// and the rest.
+pub fn split_datablock(value: &str) -> (&str, &str) {
+    match value.find('"') {
+        Some(index) => (&value[..index], value[index + 1..].trim_start()),
+        None => (value.trim(), ""),
+    }
+}
+
+/// Blockland stores brick names with a leading underscore, like `_door1`.
+pub fn object_name(value: &str) -> String {
+    value.trim().trim_start_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn splits_tags_from_values() {
+        assert_eq!(split_extra("+-OWNER 1234"), Some(("OWNER", "1234")));
+        assert_eq!(split_extra("+-EVENT\t0\t1\tonActivate"), Some(("EVENT", "0\t1\tonActivate")));
+        assert_eq!(split_extra("+-NTOBJECTNAME"), Some(("NTOBJECTNAME", "")));
+        assert_eq!(split_extra("OWNER 1234"), None);
+    }
+
+    #[test]
+    fn finds_the_first_line_with_a_tag() {
+        let brick = test_util::brick(&[
+            "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+            "+-NTOBJECTNAME _door1",
+            "+-NTOBJECTNAME _door2",
+        ]);
+        assert_eq!(find_extra(&brick, OBJECT_NAME), Some("_door1"));
+        assert_eq!(find_extra(&brick, VEHICLE), None);
+    }
+
+    #[test]
+    fn splits_datablocks_and_names() {
+        assert_eq!(split_datablock("JeepVehicle\" 1"), ("JeepVehicle", "1"));
+        assert_eq!(split_datablock(" Horse "), ("Horse", ""));
+        assert_eq!(object_name(" _door1"), "door1");
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    bounds::Bounds,
+    extra::{self, split_extra},
+    resolve_color,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, prelude::*},
+};
+
+/// Smallest and largest coordinates of a set of positions.
+pub type PositionBounds = ((f32, f32, f32), (f32, f32, f32));
+
+/// Statistics about a Blockland save as it was saved.
+#[derive(Debug, Default)]
+pub struct BlsInfo {
+    pub description: String,
+    pub brick_count: usize,
+    pub baseplate_count: usize,
+    pub ui_names: BTreeMap<String, usize>,
+    /// Bricks per colorset index.
+    pub colors: BTreeMap<u8, usize>,
+    pub color_fx: BTreeMap<u8, usize>,
+    pub shape_fx: BTreeMap<u8, usize>,
+    /// Bricks per owner BL_ID.
+    pub owners: BTreeMap<String, usize>,
+    /// How many bricks carry each kind of extra data, such as `EVENT` or `LIGHT`.
+    pub extras: BTreeMap<String, usize>,
+    /// Bounds of the brick positions, in Blockland units.
+    pub position_bounds: Option<PositionBounds>,
+}
+
+/// Statistics about Brickadia save data, either read from a brs file or
+/// produced by a conversion.
+#[derive(Debug, Default)]
+pub struct BrsInfo {
+    pub description: String,
+    pub map: String,
+    pub author: String,
+    pub brick_count: usize,
+    pub invisible_count: usize,
+    pub nocollide_count: usize,
+    pub assets: BTreeMap<String, usize>,
+    /// Bricks per color table index.
+    pub colors: BTreeMap<u32, usize>,
+    /// Bricks per custom color, as `#rrggbbaa`.
+    pub custom_colors: BTreeMap<String, usize>,
+    pub materials: BTreeMap<String, usize>,
+    pub owners: BTreeMap<String, usize>,
+    /// Bounds of the bricks, in Brickadia units.
+    pub bounds: Option<Bounds>,
+}
+
+/// Gathers statistics by reading all bricks of a bls file.
+pub fn bls_info(reader: bl_save::Reader<impl BufRead>) -> io::Result<BlsInfo> {
+    let mut info = BlsInfo {
+        description: reader.description().to_string(),
+        ..Default::default()
+    };
+
+    for brick in reader {
+        let brick = brick?;
+        let base = &brick.base;
+
+        info.brick_count += 1;
+        if base.is_baseplate {
+            info.baseplate_count += 1;
+        }
+        *info.ui_names.entry(base.ui_name.clone()).or_default() += 1;
+        *info.colors.entry(base.color_index).or_default() += 1;
+        *info.color_fx.entry(base.color_fx).or_default() += 1;
+        *info.shape_fx.entry(base.shape_fx).or_default() += 1;
+
+        let mut tags: Vec<_> = brick
+            .unknown_extra
+            .iter()
+            .filter_map(|line| split_extra(line))
+            .collect();
+
+        let owner = tags
+            .iter()
+            .find(|(tag, _)| *tag == extra::OWNER)
+            .map(|(_, id)| id.trim().to_string())
+            .unwrap_or_else(|| String::from("none"));
+        *info.owners.entry(owner).or_default() += 1;
+
+        tags.sort_unstable();
+        tags.dedup_by_key(|(tag, _)| *tag);
+        for (tag, _) in tags {
+            *info.extras.entry(tag.to_string()).or_default() += 1;
+        }
+
+        let (x, y, z) = base.position;
+        info.position_bounds = Some(match info.position_bounds {
+            Some((min, max)) => (
+                (min.0.min(x), min.1.min(y), min.2.min(z)),
+                (max.0.max(x), max.1.max(y), max.2.max(z)),
+            ),
+            None => ((x, y, z), (x, y, z)),
+        });
+    }
+
+    Ok(info)
+}
+
+/// Gathers statistics about save data.
+pub fn brs_info(data: &brs::WriteData) -> BrsInfo {
+    let mut info = BrsInfo {
+        description: data.description.clone(),
+        map: data.map.clone(),
+        author: data.author.name.clone(),
+        brick_count: data.bricks.len(),
+        ..Default::default()
+    };
+
+    for brick in &data.bricks {
+        let asset = data
+            .brick_assets
+            .get(brick.asset_name_index as usize)
+            .map(String::as_str)
+            .unwrap_or("?");
+        *info.assets.entry(asset.to_string()).or_default() += 1;
+
+        match brick.color {
+            brs::ColorMode::Set(index) => *info.colors.entry(index).or_default() += 1,
+            brs::ColorMode::Custom(_) => {
+                let color = format!("{:?}", resolve_color(data, brick.color));
+                *info.custom_colors.entry(color).or_default() += 1;
+            }
+        }
+
+        let material = data
+            .materials
+            .get(brick.material_index as usize)
+            .map(String::as_str)
+            .unwrap_or("?");
+        *info.materials.entry(material.to_string()).or_default() += 1;
+
+        let owner = data
+            .brick_owners
+            .get(brick.owner_index as usize)
+            .map(|o| o.name.as_str())
+            .unwrap_or("?");
+        *info.owners.entry(owner.to_string()).or_default() += 1;
+
+        if !brick.visibility {
+            info.invisible_count += 1;
+        }
+        if !brick.collision {
+            info.nocollide_count += 1;
+        }
+
+        let bounds = Bounds::of_brick(asset, brick);
+        info.bounds = Some(match info.bounds {
+            Some(b) => b.union(&bounds),
+            None => bounds,
+        });
+    }
+
+    info
+}
+
+/// Converts a position in Brickadia units to Blockland units.
+fn to_blockland((x, y, z): (i32, i32, i32)) -> (f32, f32, f32) {
+    (y as f32 / 20.0, x as f32 / 20.0, z as f32 / 20.0)
+}
+
+/// Converts a position in Blockland units to Brickadia units.
+fn to_brickadia((x, y, z): (f32, f32, f32)) -> (f32, f32, f32) {
+    (y * 20.0, x * 20.0, z * 20.0)
+}
+
+impl BlsInfo {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "description": self.description,
+            "brick_count": self.brick_count,
+            "baseplate_count": self.baseplate_count,
+            "ui_names": self.ui_names,
+            "colors": self.colors,
+            "color_fx": self.color_fx,
+            "shape_fx": self.shape_fx,
+            "owners": self.owners,
+            "extras": self.extras,
+            "bounds": self.position_bounds.map(|(min, max)| json!({
+                "blockland": { "min": min, "max": max },
+                "brickadia": { "min": to_brickadia(min), "max": to_brickadia(max) },
+            })),
+        })
+    }
+}
+
+impl BrsInfo {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "description": self.description,
+            "map": self.map,
+            "author": self.author,
+            "brick_count": self.brick_count,
+            "invisible_count": self.invisible_count,
+            "nocollide_count": self.nocollide_count,
+            "assets": self.assets,
+            "colors": self.colors,
+            "custom_colors": self.custom_colors,
+            "materials": self.materials,
+            "owners": self.owners,
+            "bounds": self.bounds.map(|b| json!({
+                "brickadia": { "min": b.min, "max": b.max },
+                "blockland": { "min": to_blockland(b.min), "max": to_blockland(b.max) },
+            })),
+        })
+    }
+}
+
+impl fmt::Display for BlsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_description(f, &self.description)?;
+        writeln!(f, "Bricks: {} ({} baseplates)", self.brick_count, self.baseplate_count)?;
+
+        if let Some((min, max)) = self.position_bounds {
+            writeln!(f, "Positions (Blockland): {:?} to {:?}", min, max)?;
+            writeln!(
+                f,
+                "Positions (Brickadia): {:?} to {:?}",
+                to_brickadia(min),
+                to_brickadia(max)
+            )?;
+        }
+
+        for (tag, label) in &[
+            (extra::EVENT, "events"),
+            (extra::LIGHT, "lights"),
+            (extra::EMITTER, "emitters"),
+            (extra::ITEM, "items"),
+            (extra::AUDIO_EMITTER, "music"),
+            (extra::VEHICLE, "vehicles"),
+            (extra::OBJECT_NAME, "names"),
+        ] {
+            let count = self.extras.get(*tag).copied().unwrap_or(0);
+            writeln!(f, "Bricks with {}: {}", label, count)?;
+        }
+
+        write_counts(f, "Bricks by ui_name", &self.ui_names)?;
+        write_counts(f, "Bricks by color", &self.colors)?;
+        write_counts(f, "Bricks by colorFx", &self.color_fx)?;
+        write_counts(f, "Bricks by shapeFx", &self.shape_fx)?;
+        write_counts(f, "Bricks by owner BL_ID", &self.owners)
+    }
+}
+
+impl fmt::Display for BrsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_description(f, &self.description)?;
+        writeln!(f, "Map: {}", self.map)?;
+        writeln!(f, "Author: {}", self.author)?;
+        writeln!(
+            f,
+            "Bricks: {} ({} invisible, {} without collision)",
+            self.brick_count, self.invisible_count, self.nocollide_count
+        )?;
+
+        if let Some(bounds) = self.bounds {
+            writeln!(f, "Bounds (Brickadia): {:?} to {:?}", bounds.min, bounds.max)?;
+            writeln!(
+                f,
+                "Bounds (Blockland): {:?} to {:?}",
+                to_blockland(bounds.min),
+                to_blockland(bounds.max)
+            )?;
+        }
+
+        write_counts(f, "Bricks by asset", &self.assets)?;
+        write_counts(f, "Bricks by color", &self.colors)?;
+        write_counts(f, "Bricks by custom color", &self.custom_colors)?;
+        write_counts(f, "Bricks by material", &self.materials)?;
+        write_counts(f, "Bricks by owner", &self.owners)
+    }
+}
+
+fn write_description(f: &mut fmt::Formatter, description: &str) -> fmt::Result {
+    writeln!(f, "Description:")?;
+    for line in description.lines() {
+        writeln!(f, "  {}", line)?;
+    }
+    Ok(())
+}
+
+/// Writes counts, most common first.
+fn write_counts<K: fmt::Display>(
+    f: &mut fmt::Formatter,
+    title: &str,
+    counts: &BTreeMap<K, usize>,
+) -> fmt::Result {
+    if counts.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "{}:", title)?;
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    for (key, count) in counts {
+        writeln!(f, "  {:<28} {:>6}", key.to_string(), count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{bls, convert};
+
+    const LINES: &[&str] = &[
+        "2x4\" 1 -2 0.3 0 1 3  0 0 1 1 1",
+        "+-OWNER 1234",
+        "+-EVENT\t0\t1\tonActivate\t0\tSelf\tfireRelay",
+        "+-EVENT\t1\t1\tonRelay\t0\tSelf\tdisappear\t5",
+        "2x4\" -3 4 1.5 1 0 5  0 2 0 1 0",
+        "1x1\" 0 0 0.1 0 0 5  0 0 0 0 1",
+        "+-OWNER 1234",
+    ];
+
+    #[test]
+    fn counts_bls_bricks() {
+        let save = bls(LINES);
+        let info = bls_info(bl_save::Reader::new(&save[..]).unwrap()).unwrap();
+
+        assert_eq!(info.description, "Test save");
+        assert_eq!(info.brick_count, 3);
+        assert_eq!(info.baseplate_count, 1);
+        assert_eq!(info.ui_names["2x4"], 2);
+        assert_eq!(info.colors[&5], 2);
+        assert_eq!(info.shape_fx[&2], 1);
+        assert_eq!(info.owners["1234"], 2);
+        assert_eq!(info.owners["none"], 1);
+        // Each brick counts once, however many events it has
+        assert_eq!(info.extras[extra::EVENT], 1);
+        assert_eq!(
+            info.position_bounds,
+            Some(((-3.0, -2.0, 0.1), (1.0, 4.0, 1.5)))
+        );
+        assert_eq!(info.to_json()["bounds"]["brickadia"]["max"], json!([80.0, 20.0, 30.0]));
+    }
+
+    #[test]
+    fn counts_converted_bricks() {
+        let report = convert(LINES, &Default::default());
+        let info = brs_info(&report.write_data);
+
+        assert_eq!(info.brick_count, 3);
+        assert_eq!(info.invisible_count, 1);
+        assert_eq!(info.nocollide_count, 1);
+        assert_eq!(info.assets["PB_DefaultBrick"], 3);
+        assert_eq!(info.colors.values().sum::<usize>(), 3);
+        assert!(info.bounds.is_some());
+
+        let text = info.to_string();
+        assert!(text.contains("Bricks: 3 (1 invisible, 1 without collision)"));
+        assert!(text.contains("PB_DefaultBrick"));
+    }
+}
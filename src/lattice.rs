@@ -0,0 +1,226 @@
+//! Grill and lattice plates of any size, flat, cut into corners or angled.
+//! The 8x8 lattice plate is tiled where it fits, and scaled lattice fills in
+//! the rest.
+
+use crate::types::{BrickDesc, BrickMapping};
+
+/// Side length of `B_8x8_Lattice_Plate`, in studs.
+const TILE_STUDS: u32 = 8;
+/// Half the thickness of scaled lattice, in Brickadia units.
+const LATTICE_THICKNESS: u32 = 1;
+/// Where the bars of `Grill Plate` are across its width, in Brickadia units.
+const GRILL_BARS: [i32; 3] = [-4, 0, 4];
+
+/// How a grill or lattice plate is cut or tilted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeShape {
+    /// A flat plate, like `8x8 Grill`.
+    Plate,
+    /// Half a plate cut along its diagonal, like `8x8 Grill Corner`. It keeps
+    /// the -X -Y corner, and the cut is a staircase of one stud wide strips.
+    Corner,
+    /// A plate rising a stud for every stud along its length, like
+    /// `4x8 Grill Angled`, built as steps of one stud.
+    Angled,
+}
+
+/// A grill or lattice plate, one plate thick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatticePlate {
+    /// In studs, like the first number of `8x8 Grill`.
+    pub width: u32,
+    /// In studs, like the second number of `8x8 Grill`.
+    pub length: u32,
+    pub shape: LatticeShape,
+}
+
+impl LatticePlate {
+    pub fn new(width: u32, length: u32, shape: LatticeShape) -> Self {
+        Self {
+            width,
+            length,
+            shape,
+        }
+    }
+
+    pub fn mapping(&self) -> BrickMapping {
+        // Half extents, with the width across Y like other bricks
+        let (x, y) = ((self.length * 5) as i32, (self.width * 5) as i32);
+        match self.shape {
+            LatticeShape::Plate => tiled_lattice((x, y)),
+            LatticeShape::Corner => corner_lattice(self.width, (x, y)),
+            LatticeShape::Angled => angled_lattice(self.length, (x, y)),
+        }
+    }
+}
+
+/// A flat plate with these half extents. The 8x8 lattice plate is tiled
+/// where it fits, and scaled lattice fills in the rest.
+fn tiled_lattice((x, y): (i32, i32)) -> BrickMapping {
+    let tile = (TILE_STUDS * 5) as i32;
+    let tiles = (x / tile, y / tile);
+    let tiled = (-x + tiles.0 * tile * 2, -y + tiles.1 * tile * 2);
+
+    let mut mapping = vec![];
+    for i in 0..tiles.0 {
+        for j in 0..tiles.1 {
+            mapping.push(
+                BrickDesc::new("B_8x8_Lattice_Plate")
+                    .offset((-x + tile * (2 * i + 1), -y + tile * (2 * j + 1), 0)),
+            );
+        }
+    }
+
+    // Scaled lattice along the far sides, where whole tiles don't fit
+    mapping.extend(scaled_lattice((tiled.0, -y), (x, y), 0));
+    mapping.extend(scaled_lattice((-x, tiled.1), (tiled.0, y), 0));
+    mapping
+}
+
+/// Half a plate, as strips along X that get shorter towards +Y. Strips are
+/// long enough to reach the diagonal, and strips of the same length are
+/// joined.
+fn corner_lattice(width: u32, (x, y): (i32, i32)) -> BrickMapping {
+    let length = x as u32 / 5;
+    let strip_end = |row: u32| -x + 10 * (length * (width - row)).div_ceil(width) as i32;
+
+    let mut mapping = vec![];
+    let mut start = 0;
+    for row in 1..=width {
+        if row == width || strip_end(row) != strip_end(start) {
+            let min = (-x, -y + 10 * start as i32);
+            let max = (strip_end(start), -y + 10 * row as i32);
+            mapping.extend(scaled_lattice(min, max, 0));
+            start = row;
+        }
+    }
+    mapping
+}
+
+/// A plate rising towards +X, as one step per stud of length, centered on
+/// the middle step.
+fn angled_lattice(length: u32, (x, y): (i32, i32)) -> BrickMapping {
+    (0..length as i32)
+        .filter_map(|step| {
+            let z = 10 * step + 5 - 5 * length as i32;
+            scaled_lattice((-x + 10 * step, -y), (-x + 10 * (step + 1), y), z)
+        })
+        .collect()
+}
+
+/// Lattice stretched over a rectangle at a height, if it isn't empty.
+fn scaled_lattice(min: (i32, i32), max: (i32, i32), z: i32) -> Option<BrickDesc> {
+    if min.0 >= max.0 || min.1 >= max.1 {
+        return None;
+    }
+
+    Some(
+        BrickDesc::new("BP_LatticeThin")
+            .size((
+                (max.0 - min.0) as u32 / 2,
+                (max.1 - min.1) as u32 / 2,
+                LATTICE_THICKNESS,
+            ))
+            .offset(((min.0 + max.0) / 2, (min.1 + max.1) / 2, z))
+            .rotation_offset(0),
+    )
+}
+
+/// The 1x1 piece joining two `Grill Plate`s at a right angle. Its bars come
+/// in from the -Y side and bend out to the -X side, the outer ones around
+/// the inner ones.
+pub fn grill_corner() -> BrickMapping {
+    let bar = |half: (i32, i32), center: (i32, i32)| {
+        BrickDesc::new("PB_DefaultMicroBrick")
+            .size((half.0 as u32, half.1 as u32, 2))
+            .offset((center.0, center.1, 0))
+            .rotation_offset(0)
+    };
+
+    GRILL_BARS
+        .iter()
+        .flat_map(|&at| {
+            // From the -Y edge to where the bar bends, then on to the -X edge
+            let along_y = bar((1, (at + 6) / 2), (at, (at - 4) / 2));
+            let along_x = bar(((at + 4) / 2, 1), ((at - 6) / 2, at));
+            if at + 4 > 0 {
+                vec![along_y, along_x]
+            } else {
+                vec![along_y]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        provider::{BuiltinMappings, MappingProvider},
+        test_util,
+    };
+
+    /// The area covered from above, in square Brickadia units.
+    fn area(mapping: &BrickMapping) -> u32 {
+        mapping
+            .iter()
+            .map(|desc| match desc.asset {
+                "B_8x8_Lattice_Plate" => 80 * 80,
+                _ => 4 * desc.size.0 * desc.size.1,
+            })
+            .sum()
+    }
+
+    fn map(ui_name: &str) -> BrickMapping {
+        let brick = test_util::brick(&[&format!("{ui_name}\" 0 0 0.1 0 0 0  0 0 1 1 1")]);
+        BuiltinMappings.map_brick(ui_name, &brick).unwrap()
+    }
+
+    #[test]
+    fn plates_are_tiled_and_filled() {
+        assert_eq!(map("8x8 Grill").len(), 1);
+        assert_eq!(map("8x8 Grill")[0].asset, "B_8x8_Lattice_Plate");
+
+        let plate = map("12x20 Lattice Plate");
+        let tiles = plate.iter().filter(|desc| desc.asset == "B_8x8_Lattice_Plate").count();
+        assert_eq!(tiles, 2);
+        assert_eq!(area(&plate), 120 * 200);
+    }
+
+    #[test]
+    fn corners_are_half_a_plate() {
+        let corner = map("8x8 Grill Corner");
+        assert_eq!(corner.len(), 8);
+        assert!(corner.iter().all(|desc| desc.asset == "BP_LatticeThin"));
+        // The staircase covers the triangle and the steps along the diagonal
+        assert_eq!(area(&corner), 80 * 80 / 2 + 8 * 10 * 10 / 2);
+
+        // Strips of the same length are joined
+        let wide = LatticePlate::new(6, 3, LatticeShape::Corner).mapping();
+        assert_eq!(wide.len(), 3);
+        assert_eq!(wide[0].size, (15, 10, 1));
+        assert_eq!(wide[0].offset, (0, -20, 0));
+    }
+
+    #[test]
+    fn angled_plates_rise_a_stud_per_stud() {
+        let angled = map("4x8 Grill Angled");
+        let offsets = |mapping: &BrickMapping| -> Vec<_> {
+            mapping.iter().map(|desc| desc.offset).collect()
+        };
+        assert_eq!(offsets(&angled), offsets(&map("4x8 Grill 45°")));
+        assert_eq!(angled.len(), 8);
+        for (step, desc) in angled.iter().enumerate() {
+            assert_eq!(desc.size, (5, 20, 1));
+            assert_eq!(desc.offset, (-35 + 10 * step as i32, 0, -35 + 10 * step as i32));
+        }
+    }
+
+    #[test]
+    fn grill_corner_bars_stay_inside() {
+        for desc in grill_corner() {
+            assert!(desc.offset.0.unsigned_abs() + desc.size.0 <= 5);
+            assert!(desc.offset.1.unsigned_abs() + desc.size.1 <= 5);
+        }
+    }
+}
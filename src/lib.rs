@@ -4,18 +4,57 @@ use std::{
     convert::TryInto,
     io::{self, prelude::*},
     ops::Neg,
+    sync::Arc,
 };
 
 pub use bl_save;
 pub use brs;
 
+pub mod archive;
+pub mod baseplates;
+pub mod bounds;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod components;
+pub mod diff;
+pub mod doors;
+pub mod events;
+pub mod extra;
+pub mod info;
+pub mod lattice;
+pub mod limits;
+pub mod merge;
+pub mod mesh;
+pub mod obj;
+pub mod octo;
+pub mod preview;
+pub mod provider;
+pub mod roads;
+pub mod spawns;
+pub mod split;
+pub mod terrain;
+pub mod volumes;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod table;
 mod types;
 #[macro_use]
 mod misc;
 mod mappings;
-
-use mappings::{BRICK_MAP_LITERAL, BRICK_MAP_REGEX};
-use types::{BrickDesc, BrickMapping};
+#[cfg(test)]
+mod test_util;
+
+use baseplates::{baseplate_descs, is_baseplate, BaseplateOptions};
+use components::{attach_components, Component, UntranslatedEvent};
+use extra::{find_extra, object_name, split_datablock};
+use limits::{SizeLimits, SizeReport};
+use octo::{octo_descs, OctoStyle};
+use spawns::{brick_spawn, Spawn};
+use volumes::{brick_volume, Volume};
+use provider::{BuiltinMappings, MappingProvider};
+use roads::RoadPalette;
+use terrain::{build_terrain, TerrainBrick, TerrainOptions, TerrainReport};
+pub use types::{BrickDesc, BrickMapping};
 
 // Keep this in sync. Would be nice to just determine the indices at compile time.
 const FIXED_MATERIAL_TABLE: &[&str] = &["BMC_Plastic", "BMC_Glow", "BMC_Metallic"];
@@ -25,37 +64,117 @@ const BMC_METALLIC: usize = 2;
 
 const BRICK_OWNER: usize = 0;
 
+/// Settings for a conversion.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Brickadia audio assets for Blockland music loop datablocks, like `musicData_Ambient_Drone`.
+    pub music: HashMap<String, String>,
+    /// Brickadia vehicle prefabs or placeholders for Blockland vehicles, like `Jeep`.
+    pub vehicles: HashMap<String, String>,
+    /// When the save was made, instead of the current time.
+    pub save_time: Option<DateTime<Utc>>,
+    /// Where mappings come from instead of [`BuiltinMappings`].
+    pub mappings: Option<Arc<dyn MappingProvider>>,
+    /// Colors of asphalt and road markings.
+    pub road_palette: RoadPalette,
+    /// Fit ModTer bricks to their neighbours instead of converting each on its own.
+    pub terrain: Option<TerrainOptions>,
+    /// How baseplates are built.
+    pub baseplates: BaseplateOptions,
+    /// How large converted bricks may be.
+    pub size_limits: SizeLimits,
+    /// How octos are built.
+    pub octo_style: OctoStyle,
+}
+
 pub struct ConvertReport {
     pub write_data: brs::WriteData,
     pub unknown_ui_names: HashMap<String, usize>,
     pub count_success: usize,
     pub count_failure: usize,
+    /// For each brick in `write_data`, the index of the Blockland brick it was converted from.
+    pub brick_sources: Vec<usize>,
+    /// Positions of the Blockland bricks that failed to convert, in Brickadia units.
+    pub failed_positions: Vec<(i32, i32, i32)>,
+    /// The ui_name of every Blockland brick, by index.
+    pub ui_names: Vec<String>,
+    /// Names of bricks in `write_data` given by `+-NTOBJECTNAME`, by brick index.
+    /// Every brick converted from a named Blockland brick carries its name.
+    pub brick_names: Vec<(usize, String)>,
+    /// Components of bricks in `write_data`, by brick index.
+    pub components: Vec<(usize, Component)>,
+    /// Spawn points, vehicle spawns and checkpoints among the converted bricks.
+    pub spawns: Vec<Spawn>,
+    /// Water and zone bricks, which are left out of `write_data`.
+    pub volumes: Vec<Volume>,
+    /// Music loops of converted bricks missing from [`ConvertOptions::music`],
+    /// with how many bricks play them.
+    pub unknown_music: HashMap<String, usize>,
+    /// Vehicles of vehicle spawns missing from [`ConvertOptions::vehicles`],
+    /// with how many spawns hold them.
+    pub unknown_vehicles: HashMap<String, usize>,
+    /// Events of converted bricks that couldn't be translated into components.
+    pub untranslated_events: Vec<UntranslatedEvent>,
+    /// What [`ConvertOptions::terrain`] changed.
+    pub terrain: TerrainReport,
+    /// Indices of the baseplate bricks in `write_data`, if
+    /// [`BaseplateOptions::ground_layer`] is set.
+    pub ground_layer: Vec<usize>,
+    /// What [`ConvertOptions::size_limits`] changed or found.
+    pub size_limits: SizeReport,
+}
+
+impl ConvertReport {
+    /// The outcome of the conversion without the save data, including the
+    /// [sidecar](components::sidecar_json).
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "count_success": self.count_success,
+            "count_failure": self.count_failure,
+            "count_output": self.write_data.bricks.len(),
+            "unknown_ui_names": self.unknown_ui_names,
+            "unknown_music": self.unknown_music,
+            "unknown_vehicles": self.unknown_vehicles,
+            "oversized": self.size_limits.oversized,
+            "sidecar": components::sidecar_json(self),
+        })
+    }
+}
+
+/// Converts a save and writes the result in brs format to `writer`.
+///
+/// The returned report still holds the written `write_data`.
+pub fn convert_to_writer(
+    reader: bl_save::Reader<impl BufRead>,
+    writer: &mut impl Write,
+) -> io::Result<ConvertReport> {
+    let report = convert(reader)?;
+    brs::write_save(writer, &report.write_data)?;
+    Ok(report)
 }
 
 pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertReport> {
-    let data = brs::WriteData {
-        map: String::from("Unknown"),
-        author: brs::User {
-            id: Uuid::nil(),
-            name: String::from("Unknown"),
-        },
-        description: reader.description().to_string(),
-        save_time: Utc::now(),
-        mods: vec![],
-        brick_assets: vec![],
-        colors: reader.colors().iter().map(|c| map_color(*c)).collect(),
-        materials: FIXED_MATERIAL_TABLE
-            .iter()
-            .map(|s| String::from(*s))
-            .collect(),
-        brick_owners: vec![brs::User {
-            id: Uuid::from_bytes([u8::max_value(); 16]),
-            name: String::from("PUBLIC"),
-        }],
-        bricks: Vec::with_capacity(reader.brick_count().unwrap_or(100).min(10_000_000)),
-    };
+    convert_with_options(reader, &ConvertOptions::default())
+}
+
+pub fn convert_with_options(
+    reader: bl_save::Reader<impl BufRead>,
+    options: &ConvertOptions,
+) -> io::Result<ConvertReport> {
+    let mut data = new_write_data(
+        reader.description().to_string(),
+        reader.colors().iter().map(|c| map_color(*c)).collect(),
+        options.save_time.unwrap_or_else(Utc::now),
+    );
+    data.bricks.reserve(reader.brick_count().unwrap_or(100).min(10_000_000));
+
+    let mappings = options
+        .mappings
+        .as_deref()
+        .unwrap_or(&BuiltinMappings as &dyn MappingProvider);
 
     let mut converter = Converter {
+        mappings,
         write_data: data,
         asset_map: HashMap::new(),
         unknown_ui_names: HashMap::new(),
@@ -65,9 +184,24 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
     let mut count_failure = 0;
 
     let mut non_prio = Vec::new();
-
-    for from in reader {
+    let mut brick_sources = Vec::new();
+    let mut non_prio_sources = Vec::new();
+    let mut failed_positions = Vec::new();
+    let mut ui_names = Vec::new();
+    let mut source_names = HashMap::new();
+    let mut pending_components = Vec::new();
+    let mut spawns = Vec::new();
+    let mut volumes = Vec::new();
+    let mut unknown_music = HashMap::new();
+    let mut unknown_vehicles = HashMap::new();
+    let mut untranslated_events = Vec::new();
+    let mut terrain = Vec::new();
+    let mut ground_layer = Vec::new();
+    let mut size_report = SizeReport::default();
+
+    for (source, from) in reader.enumerate() {
         let from = from?;
+        ui_names.push(from.base.ui_name.clone());
         let option = converter.map_brick(&from);
 
         let mappings = match option {
@@ -77,10 +211,86 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
             }
             None => {
                 count_failure += 1;
+                failed_positions.push(map_position(from.base.position));
                 continue;
             }
         };
 
+        if let Some(name) = find_extra(&from, extra::OBJECT_NAME) {
+            source_names.insert(source, object_name(name));
+        }
+
+        if let Some(spawn) = brick_spawn(
+            source,
+            &from,
+            &converter.write_data.colors,
+            &options.vehicles,
+        ) {
+            if let Some(vehicle) = spawn.vehicle.as_ref().filter(|v| v.prefab.is_none()) {
+                *unknown_vehicles.entry(vehicle.datablock.clone()).or_default() += 1;
+            }
+            pending_components.push((
+                source,
+                Component::Spawn {
+                    kind: spawn.kind,
+                    vehicle: spawn.vehicle.clone(),
+                },
+            ));
+            spawns.push(spawn);
+        }
+
+        if let Some(volume) = brick_volume(source, &from) {
+            volumes.push(volume);
+        }
+
+        if let Some(door) = doors::parse_door(&from.base.ui_name) {
+            pending_components.push((source, Component::Door(door)));
+        }
+
+        if let Some(music) = find_extra(&from, extra::AUDIO_EMITTER) {
+            let music = split_datablock(music).0;
+            match options.music.get(music) {
+                Some(asset) => pending_components.push((
+                    source,
+                    Component::AudioEmitter {
+                        music: music.to_string(),
+                        asset: asset.clone(),
+                    },
+                )),
+                None => *unknown_music.entry(music.to_string()).or_default() += 1,
+            }
+        }
+
+        let (interactions, untranslated) =
+            events::brick_interactions(&from, &converter.write_data.colors);
+        pending_components.extend(
+            interactions
+                .into_iter()
+                .map(|i| (source, Component::Interaction(i))),
+        );
+        untranslated_events.extend(
+            untranslated
+                .into_iter()
+                .map(|event| UntranslatedEvent { source, event }),
+        );
+
+        let mappings: Vec<_> = mappings
+            .into_iter()
+            .flat_map(|desc| octo_descs(desc, options.octo_style))
+            .flat_map(|desc| {
+                if is_baseplate(&from, &desc) {
+                    baseplate_descs(desc, &options.baseplates, options.size_limits.max_extent)
+                } else {
+                    vec![desc]
+                }
+            })
+            .flat_map(|desc| {
+                options
+                    .size_limits
+                    .apply(desc, &from.base.ui_name, &mut size_report)
+            })
+            .collect();
+
         // match from.base.print.as_str() {
         //     "A" => {
         //
@@ -101,7 +311,11 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
             inverted_wedge_rotate,
             modter,
             rotate_by_direction,
-            nocollide
+            nocollide,
+            baseplate,
+            paint,
+            octo: _,
+            octo_style: _,
         } in mappings
         {
             let asset_name_index = converter.asset(asset);
@@ -110,10 +324,11 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
             let rotated_xy = rotate_offset((offset.0, offset.1), from.base.angle);
             let offset = (rotated_xy.0, rotated_xy.1, offset.2);
 
+            let base_position = map_position(from.base.position);
             let position = (
-                (from.base.position.1 * 20.0) as i32 + offset.0,
-                (from.base.position.0 * 20.0) as i32 + offset.1,
-                (from.base.position.2 * 20.0) as i32 + offset.2,
+                base_position.0 + offset.0,
+                base_position.1 + offset.1,
+                base_position.2 + offset.2,
             );
 
             let material_index = match from.base.color_fx {
@@ -122,6 +337,11 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
                 _ => BMC_PLASTIC,
             };
 
+            let color_override = match paint {
+                Some(paint) => Some(options.road_palette.color(paint)),
+                None => color_override,
+            };
+
             let color_index = match color_override {
                 Some(color) => converter.color(color) as u32,
                 None => u32::from(from.base.color_index),
@@ -205,34 +425,122 @@ pub fn convert(reader: bl_save::Reader<impl BufRead>) -> io::Result<ConvertRepor
 
             if non_priority || (modter && !brick.visibility) {
                 non_prio.push(brick);
+                non_prio_sources.push(source);
+            } else if modter && options.terrain.is_some() {
+                terrain.push(TerrainBrick {
+                    asset,
+                    brick,
+                    source,
+                });
             } else {
+                if baseplate && options.baseplates.ground_layer {
+                    ground_layer.push(converter.write_data.bricks.len());
+                }
                 converter.write_data.bricks.push(brick);
+                brick_sources.push(source);
             }
         }
     }
-    
+
+    let mut terrain_report = TerrainReport::default();
+    if let Some(terrain_options) = &options.terrain {
+        let others: Vec<_> = converter
+            .write_data
+            .bricks
+            .iter()
+            .chain(&non_prio)
+            .map(|brick| {
+                let asset = &converter.write_data.brick_assets[brick.asset_name_index as usize];
+                (asset.as_str(), brick)
+            })
+            .collect();
+        let (built, report) = build_terrain(terrain, &others, terrain_options);
+        terrain_report = report;
+
+        for TerrainBrick {
+            asset,
+            mut brick,
+            source,
+        } in built
+        {
+            brick.asset_name_index = converter.asset(asset) as u32;
+            converter.write_data.bricks.push(brick);
+            brick_sources.push(source);
+        }
+    }
+
     converter.write_data.bricks.append(&mut non_prio);
+    brick_sources.append(&mut non_prio_sources);
+    let components = attach_components(&converter.write_data, &brick_sources, pending_components);
+    let brick_names = brick_sources
+        .iter()
+        .enumerate()
+        .filter_map(|(index, source)| Some((index, source_names.get(source)?.clone())))
+        .collect();
 
     Ok(ConvertReport {
         write_data: converter.write_data,
         unknown_ui_names: converter.unknown_ui_names,
         count_success,
         count_failure,
+        brick_sources,
+        failed_positions,
+        ui_names,
+        brick_names,
+        components,
+        spawns,
+        volumes,
+        unknown_music,
+        unknown_vehicles,
+        untranslated_events,
+        terrain: terrain_report,
+        ground_layer,
+        size_limits: size_report,
     })
 }
 
-struct Converter {
+/// Save data with the fixed tables filled in and no bricks.
+pub(crate) fn new_write_data(
+    description: String,
+    colors: Vec<brs::Color>,
+    save_time: DateTime<Utc>,
+) -> brs::WriteData {
+    brs::WriteData {
+        map: String::from("Unknown"),
+        author: brs::User {
+            id: Uuid::nil(),
+            name: String::from("Unknown"),
+        },
+        description,
+        save_time,
+        mods: vec![],
+        brick_assets: vec![],
+        colors,
+        materials: FIXED_MATERIAL_TABLE
+            .iter()
+            .map(|s| String::from(*s))
+            .collect(),
+        brick_owners: vec![brs::User {
+            id: Uuid::from_bytes([u8::MAX; 16]),
+            name: String::from("PUBLIC"),
+        }],
+        bricks: vec![],
+    }
+}
+
+struct Converter<'a> {
+    mappings: &'a dyn MappingProvider,
     write_data: brs::WriteData,
     asset_map: HashMap<String, usize>,
     unknown_ui_names: HashMap<String, usize>,
 }
 
-impl Converter {
+impl Converter<'_> {
     fn map_brick(&mut self, from: &bl_save::Brick) -> Option<BrickMapping> {
-        let mapping = map_brick(from);
+        let mapping = self.mappings.map_brick(&from.base.ui_name, from);
 
         if cfg!(debug_assertions) {
-            println!("mapped '{}' to {:?}", from.base.ui_name, mapping);
+            eprintln!("mapped '{}' to {:?}", from.base.ui_name, mapping);
         }
 
         if mapping.is_none() {
@@ -271,20 +579,9 @@ impl Converter {
     }
 }
 
-fn map_brick(from: &bl_save::Brick) -> Option<BrickMapping> {
-    let ui_name = from.base.ui_name.as_str();
-
-    if let Some(mapping) = BRICK_MAP_LITERAL.get(ui_name) {
-        return Some(mapping.clone());
-    }
-
-    for (regex, func) in BRICK_MAP_REGEX.iter() {
-        if let Some(captures) = regex.captures(ui_name) {
-            return func(captures, from);
-        }
-    }
-
-    None
+/// Converts a Blockland position to Brickadia units, swapping X and Y.
+fn map_position((x, y, z): (f32, f32, f32)) -> (i32, i32, i32) {
+    ((y * 20.0) as i32, (x * 20.0) as i32, (z * 20.0) as i32)
 }
 
 fn map_color((r, g, b, a): (f32, f32, f32, f32)) -> brs::Color {
@@ -295,14 +592,26 @@ fn map_color((r, g, b, a): (f32, f32, f32, f32)) -> brs::Color {
     let a = gamma_expansion(a);
 
     // Convert to 0-255
-    let r = (r * 255.0).max(0.0).min(255.0) as u8;
-    let g = (g * 255.0).max(0.0).min(255.0) as u8;
-    let b = (b * 255.0).max(0.0).min(255.0) as u8;
-    let a = (a * 255.0).max(0.0).min(255.0) as u8;
+    let r = (r * 255.0).clamp(0.0, 255.0) as u8;
+    let g = (g * 255.0).clamp(0.0, 255.0) as u8;
+    let b = (b * 255.0).clamp(0.0, 255.0) as u8;
+    let a = (a * 255.0).clamp(0.0, 255.0) as u8;
 
     brs::Color::from_rgba(r, g, b, a)
 }
 
+/// The palette color or custom color of a brick.
+pub(crate) fn resolve_color(data: &brs::WriteData, color: brs::ColorMode) -> brs::Color {
+    match color {
+        brs::ColorMode::Set(index) => data
+            .colors
+            .get(index as usize)
+            .copied()
+            .unwrap_or_else(|| brs::Color::from_rgba(255, 255, 255, 255)),
+        brs::ColorMode::Custom(color) => color,
+    }
+}
+
 fn gamma_expansion(u: f32) -> f32 {
     if u <= 0.04045 {
         return u / 12.92;
@@ -0,0 +1,231 @@
+//! Brickadia rejects procedural bricks past a certain size, and mappings that
+//! scale with the ui_name can go past it. Oversized boxes are split into a grid
+//! of smaller boxes filling the same space, and other bricks are reported.
+
+use crate::types::BrickDesc;
+use std::collections::HashMap;
+
+/// Largest half extent of a procedural brick Brickadia accepts, in Brickadia units.
+pub const MAX_BRICK_EXTENT: u32 = 500;
+
+/// Assets that look the same split into a grid of smaller bricks.
+const SPLITTABLE_ASSETS: &[&str] = &[
+    "PB_DefaultBrick",
+    "PB_DefaultMicroBrick",
+    "PB_DefaultTile",
+    "PB_DefaultSmoothTile",
+    "BP_LatticeThin",
+];
+
+/// How large converted bricks may be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimits {
+    /// Largest half extent along any axis, in Brickadia units.
+    pub max_extent: u32,
+    /// Split oversized bricks where possible, instead of only reporting them.
+    pub split: bool,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_extent: MAX_BRICK_EXTENT,
+            split: true,
+        }
+    }
+}
+
+/// What [`SizeLimits`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeReport {
+    /// How many oversized bricks were split.
+    pub split: usize,
+    /// How many bricks they were split into.
+    pub split_into: usize,
+    /// ui_names of Blockland bricks that converted to bricks still too large,
+    /// with how many such bricks each.
+    pub oversized: HashMap<String, usize>,
+}
+
+impl SizeLimits {
+    fn fits(&self, desc: &BrickDesc) -> bool {
+        let (x, y, z) = desc.size;
+        x.max(y).max(z) <= self.max_extent
+    }
+
+    /// Checks a brick of a mapping, splitting it if it's too large.
+    /// Baseplates are only checked, as they're split by their own options.
+    pub(crate) fn apply(
+        &self,
+        desc: BrickDesc,
+        ui_name: &str,
+        report: &mut SizeReport,
+    ) -> Vec<BrickDesc> {
+        if self.fits(&desc) {
+            return vec![desc];
+        }
+
+        if self.split && !desc.baseplate && splittable(&desc) {
+            let chunks = split_desc(desc, self.max_extent);
+            report.split += 1;
+            report.split_into += chunks.len();
+            return chunks;
+        }
+
+        *report.oversized.entry(ui_name.to_string()).or_default() += 1;
+        vec![desc]
+    }
+}
+
+/// Whether a brick's size maps straight onto its axes, and splitting it
+/// doesn't change how it looks.
+fn splittable(desc: &BrickDesc) -> bool {
+    SPLITTABLE_ASSETS.contains(&desc.asset)
+        && desc.direction_override.is_none()
+        && !desc.microwedge_rotate
+        && !desc.rotate_by_direction
+}
+
+/// Splits a box into a grid of boxes no larger than `max_extent` along any
+/// axis, filling the same space.
+pub(crate) fn split_desc(desc: BrickDesc, max_extent: u32) -> Vec<BrickDesc> {
+    // Sizes are along the brick, offsets along the save before the brick turns
+    let turned = desc.rotation_offset % 2 == 1;
+    let (along_x, along_y) = if turned {
+        (desc.size.1, desc.size.0)
+    } else {
+        (desc.size.0, desc.size.1)
+    };
+
+    let mut chunks = vec![];
+    for (x_offset, x_extent) in chunks_along(along_x, max_extent) {
+        for (y_offset, y_extent) in chunks_along(along_y, max_extent) {
+            for (z_offset, z_extent) in chunks_along(desc.size.2, max_extent) {
+                let size = if turned {
+                    (y_extent, x_extent, z_extent)
+                } else {
+                    (x_extent, y_extent, z_extent)
+                };
+                chunks.push(desc.clone().size(size).offset((
+                    desc.offset.0 + x_offset,
+                    desc.offset.1 + y_offset,
+                    desc.offset.2 + z_offset,
+                )));
+            }
+        }
+    }
+    chunks
+}
+
+/// Splits a half extent into chunks no larger than `max_extent`, as their
+/// centers relative to the middle and their half extents. Chunks are whole
+/// studs when the extent is.
+fn chunks_along(extent: u32, max_extent: u32) -> Vec<(i32, u32)> {
+    if extent <= max_extent {
+        return vec![(0, extent)];
+    }
+
+    let step = if extent.is_multiple_of(5) && max_extent >= 5 { 5 } else { 1 };
+    let steps = extent / step;
+    let count = steps.div_ceil((max_extent / step).max(1));
+    let mut start = -(extent as i32);
+    (0..count)
+        .map(|i| {
+            // Spread the leftover steps over the first chunks
+            let chunk = (steps / count + u32::from(i < steps % count)) * step;
+            let center = start + chunk as i32;
+            start += 2 * chunk as i32;
+            (center, chunk)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    #[test]
+    fn chunks_cover_the_extent() {
+        assert_eq!(chunks_along(300, 500), [(0, 300)]);
+
+        for (extent, max_extent) in [(1280, 500), (1001, 500), (640, 160), (7, 3)] {
+            let chunks = chunks_along(extent, max_extent);
+            assert_eq!(chunks.iter().map(|&(_, chunk)| chunk).sum::<u32>(), extent);
+            assert!(chunks.iter().all(|&(_, chunk)| chunk <= max_extent));
+
+            // Side by side from one end to the other
+            let mut start = -(extent as i32);
+            for (center, chunk) in chunks {
+                assert_eq!(center - chunk as i32, start);
+                start = center + chunk as i32;
+            }
+            assert_eq!(start, extent as i32);
+        }
+
+        // Whole studs where the extent is
+        let chunks = chunks_along(1280, 500);
+        assert!(chunks.iter().all(|&(_, chunk)| chunk.is_multiple_of(5)));
+    }
+
+    #[test]
+    fn splits_turned_bricks_along_the_save() {
+        let desc = BrickDesc::new("PB_DefaultBrick")
+            .size((600, 100, 2))
+            .offset((10, 20, 0))
+            .rotation_offset(1);
+        let chunks = split_desc(desc, 500);
+
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            assert_eq!(chunk.size, (300, 100, 2));
+            assert_eq!(chunk.offset.0, 10);
+        }
+        assert_eq!(chunks[0].offset.1, 20 - 300);
+        assert_eq!(chunks[1].offset.1, 20 + 300);
+    }
+
+    #[test]
+    fn reports_what_it_cannot_split() {
+        let limits = SizeLimits::default();
+        let mut report = SizeReport::default();
+
+        let small = BrickDesc::new("PB_DefaultBrick").size((10, 10, 6));
+        assert_eq!(limits.apply(small, "2x2", &mut report).len(), 1);
+
+        let large = BrickDesc::new("PB_DefaultBrick").size((1000, 10, 6));
+        assert_eq!(limits.apply(large, "Large", &mut report).len(), 2);
+
+        let wedge = BrickDesc::new("PB_DefaultMicroWedge").size((1000, 10, 6));
+        assert_eq!(limits.apply(wedge, "Wedge", &mut report).len(), 1);
+
+        let baseplate = BrickDesc::new("PB_DefaultBrick").size((1000, 1000, 2)).baseplate();
+        assert_eq!(limits.apply(baseplate, "Base", &mut report).len(), 1);
+
+        let unsplit = SizeLimits {
+            split: false,
+            ..limits
+        };
+        let large = BrickDesc::new("PB_DefaultBrick").size((1000, 10, 6));
+        assert_eq!(unsplit.apply(large, "Large", &mut report).len(), 1);
+
+        assert_eq!((report.split, report.split_into), (1, 2));
+        let mut oversized: Vec<_> = report.oversized.into_iter().collect();
+        oversized.sort();
+        assert_eq!(
+            oversized,
+            [("Base".to_string(), 1), ("Large".to_string(), 1), ("Wedge".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn splits_tall_cubes() {
+        let cube = "64x Cube 4H\" 0 0 32 0 0 3  0 0 1 1 1";
+        let report = convert(&[cube], &ConvertOptions::default());
+        let bricks = &report.write_data.bricks;
+
+        assert_eq!(bricks.len(), 3);
+        assert!(bricks.iter().all(|brick| brick.size.0 == 320 && brick.size.1 == 320));
+        assert_eq!(bricks.iter().map(|brick| brick.size.2).sum::<u32>(), 1280);
+    }
+}
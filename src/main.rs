@@ -1,11 +1,32 @@
-use bls2brs::{bl_save, brs, convert};
+use bls2brs::{
+    archive::convert_archive,
+    baseplates::BaseplateAsset,
+    bl_save, brs,
+    components::sidecar_json,
+    convert, convert_with_options,
+    diff::{diff, Manifest},
+    info::{bls_info, brs_info},
+    merge::{merge, MergeInput},
+    obj::{write_mtl, write_obj},
+    octo::OctoStyle,
+    preview::{render_preview, PreviewOptions, MAX_PREVIEW_SIZE},
+    roads::RoadPalette,
+    spawns::SpawnKind,
+    split::{split, SplitOptions},
+    terrain::TerrainOptions,
+    ConvertOptions, ConvertReport,
+};
 use std::{
-    fs::File,
+    collections::HashMap,
+    fs::{self, File},
     ffi::OsStr,
-    io::BufReader,
-    path::{Path, PathBuf},
+    io::{self, prelude::*, BufReader, BufWriter},
+    path::{Component, Path, PathBuf},
 };
 
+/// Path that stands for stdin when given as input and stdout when given as output.
+const STDIO_PATH: &str = "-";
+
 fn main() {
     eprintln!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
     eprintln!();
@@ -21,44 +42,585 @@ fn main() {
 }
 
 fn run() -> Result<(), String> {
-    let args = parse_args()
-        .map_err(|_| String::from("Error: No bls files given. Drag them onto this program's executable file. (Not this window! This is just an error message, not the program itself.)"))?;
+    let args = parse_args()?;
+
+    match args.command {
+        Command::Convert => run_convert(&args),
+        Command::Merge => run_merge(&args),
+        Command::Info => run_info(&args),
+        Command::Diff => run_diff(&args),
+    }
+}
+
+fn run_convert(args: &Args) -> Result<(), String> {
+    let single_output = args.output_path.is_some()
+        || args.obj_path.is_some()
+        || args.preview_path.is_some()
+        || args.manifest_path.is_some()
+        || args.sidecar_path.is_some();
+
+    if single_output && args.input_paths.len() > 1 {
+        return Err(String::from("Error: An output path can only be given for a single input."));
+    }
 
     for (i, input_path) in args.input_paths.iter().enumerate() {
         if i > 0 {
-            println!();
+            eprintln!();
         }
 
         let input_path = PathBuf::from(input_path);
 
-        println!("Converting {}", input_path.display());
+        if is_stdio(&input_path) {
+            eprintln!("Converting from stdin");
+        } else {
+            eprintln!("Converting {}", input_path.display());
 
-        if input_path.extension() != Some(OsStr::new("bls")) {
-            println!("Extension is not .bls, skipping");
-            continue;
+            if is_zip(&input_path) {
+                let output_path = match &args.output_path {
+                    Some(output_path) => PathBuf::from(output_path),
+                    None => input_path.with_extension(""),
+                };
+
+                convert_zip(&input_path, &output_path, &args.convert)
+                    .map_err(|e| format!("Error converting {}: {}", input_path.display(), e))?;
+                continue;
+            }
+
+            if input_path.extension() != Some(OsStr::new("bls")) {
+                eprintln!("Extension is not .bls, skipping");
+                continue;
+            }
         }
 
-        let mut output_path = input_path.clone();
+        let output_path = match &args.output_path {
+            Some(output_path) => PathBuf::from(output_path),
+            None if is_stdio(&input_path) => PathBuf::from(STDIO_PATH),
+            None => input_path.with_extension("brs"),
+        };
+
+        convert_one(&input_path, &output_path, args)
+            .map_err(|e| format!("Error converting {}: {}", input_path.display(), e))?;
+    }
+
+    Ok(())
+}
 
-        output_path.set_extension("brs");
+fn run_merge(args: &Args) -> Result<(), String> {
+    let output_path = args
+        .output_path
+        .as_ref()
+        .ok_or_else(|| String::from("Error: Merging requires an output path (-o)."))?;
+
+    let mut inputs = Vec::new();
+
+    for (input_path, offset) in args.input_paths.iter().zip(&args.offsets) {
+        let input_path = PathBuf::from(input_path);
+        eprintln!("Converting {}", input_path.display());
 
-        convert_one(&input_path, &output_path)
+        let converted = read_and_convert(&input_path, &args.convert)
             .map_err(|e| format!("Error converting {}: {}", input_path.display(), e))?;
+        inputs.push(MergeInput {
+            report: converted,
+            offset: *offset,
+        });
+    }
+
+    eprintln!();
+    eprintln!("Merging {} saves", inputs.len());
+
+    let merged = merge(inputs);
+
+    let mut overlap_counts: HashMap<(usize, usize), usize> = HashMap::new();
+    for overlap in &merged.overlaps {
+        *overlap_counts
+            .entry((overlap.input, overlap.other_input))
+            .or_default() += 1;
+    }
+    let mut overlap_counts: Vec<_> = overlap_counts.into_iter().collect();
+    overlap_counts.sort();
+    for ((input, other_input), count) in overlap_counts {
+        eprintln!(
+            "{} bricks from {} overlap bricks from {}",
+            count, args.input_paths[input], args.input_paths[other_input],
+        );
+    }
+
+    print_report(
+        &merged.unknown_ui_names,
+        merged.count_success,
+        merged.count_failure,
+        merged.write_data.bricks.len(),
+    );
+
+    write_brs(Path::new(output_path), &merged.write_data)
+}
+
+fn run_info(args: &Args) -> Result<(), String> {
+    let mut json = Vec::new();
+
+    for (i, input_path) in args.input_paths.iter().enumerate() {
+        let sections = describe(Path::new(input_path))
+            .map_err(|e| format!("Error reading {}: {}", input_path, e))?;
+
+        if args.json {
+            let mut object = serde_json::Map::new();
+            object.insert(String::from("path"), input_path.as_str().into());
+            for (title, _, value) in sections {
+                object.insert(title.to_lowercase(), value);
+            }
+            json.push(serde_json::Value::Object(object));
+            continue;
+        }
+
+        if i > 0 {
+            println!();
+        }
+        println!("{}", input_path);
+        for (title, text, _) in sections {
+            println!();
+            println!("[{}]", title);
+            print!("{}", text);
+        }
+    }
+
+    if args.json {
+        let json = if json.len() == 1 {
+            json.remove(0)
+        } else {
+            serde_json::Value::Array(json)
+        };
+        println!("{:#}", json);
     }
 
     Ok(())
 }
 
-fn convert_one(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<(), String> {
-    let input_path = input_path.as_ref();
+fn run_diff(args: &Args) -> Result<(), String> {
+    let (old_path, new_path) = match args.input_paths.as_slice() {
+        [old_path, new_path] => (Path::new(old_path), Path::new(new_path)),
+        _ => return Err(String::from("Error: Diffing requires exactly two files.")),
+    };
+
+    if is_bls(old_path) && is_bls(new_path) {
+        return Err(String::from(
+            "Error: Both saves would be converted the same way. \
+             Diff a manifest or brs file from an earlier conversion against the save instead.",
+        ));
+    }
+
+    let old = load_manifest(old_path, args)?;
+    let new = load_manifest(new_path, args)?;
+
+    if old.ui_names != new.ui_names {
+        return Err(format!(
+            "Error: {} and {} are not conversions of the same save.",
+            old_path.display(),
+            new_path.display(),
+        ));
+    }
+
+    let report = diff(&old, &new);
+
+    if args.json {
+        println!("{:#}", report.to_json());
+    } else {
+        print!("{}", report);
+    }
+
+    Ok(())
+}
+
+/// Reads a manifest, or converts a bls file to get one. A brs file is read
+/// along with the manifest written next to it, as `<name>.manifest.json`.
+fn load_manifest(path: &Path, args: &Args) -> Result<Manifest, String> {
+    if path.extension() == Some(OsStr::new("json")) {
+        return read_manifest(path);
+    }
+
+    if path.extension() == Some(OsStr::new("brs")) {
+        let manifest_path = brs_manifest_path(path);
+        if !manifest_path.exists() {
+            return Err(format!(
+                "Error: {} doesn't record which Blockland brick each brick came from. \
+                 Convert it with --manifest {} to diff it.",
+                path.display(),
+                manifest_path.display(),
+            ));
+        }
+
+        let converted = read_manifest(&manifest_path)?;
+        let write_data = read_brs(path)?;
+        return Manifest::from_brs(&write_data, &converted)
+            .map_err(|e| format!("Error reading {}: {}", path.display(), e));
+    }
+
+    eprintln!("Converting {}", path.display());
+    let converted = read_and_convert(path, &args.convert)
+        .map_err(|e| format!("Error converting {}: {}", path.display(), e))?;
+    eprintln!();
+    Ok(Manifest::from_report(&converted))
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    let file = errmsg(File::open(path), "Failed to open manifest")?;
+    let json: serde_json::Value = errmsg(
+        serde_json::from_reader(BufReader::new(file)),
+        "Failed to read manifest",
+    )?;
+    errmsg(Manifest::from_json(&json), "Failed to read manifest")
+}
+
+/// Where the manifest of a brs file is looked for, like `save.manifest.json` for `save.brs`.
+fn brs_manifest_path(path: &Path) -> PathBuf {
+    path.with_extension("manifest.json")
+}
+
+fn read_brs(path: &Path) -> Result<brs::WriteData, String> {
+    let file = errmsg(File::open(path), "Failed to open file")?;
+    let reader = errmsg(brs::Reader::new(BufReader::new(file)), "Failed to read brs file")?;
+    errmsg(
+        reader
+            .read_header1()
+            .and_then(|r| r.read_header2())
+            .and_then(|r| r.into_write_data()),
+        "Failed to read brs file",
+    )
+}
+
+fn is_bls(path: &Path) -> bool {
+    !matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("json") | Some("brs")
+    )
+}
+
+/// Reads a bls or brs file and describes it in titled sections, as text and as JSON.
+/// Bls files are also converted to describe the result.
+fn describe(input_path: &Path) -> Result<Vec<(&'static str, String, serde_json::Value)>, String> {
+    let open = || errmsg(File::open(input_path), "Failed to open file").map(BufReader::new);
+
+    if input_path.extension() == Some(OsStr::new("brs")) {
+        let write_data = read_brs(input_path)?;
+        let info = brs_info(&write_data);
+        return Ok(vec![("Brickadia", info.to_string(), info.to_json())]);
+    }
+
+    let reader = errmsg(bl_save::Reader::new(open()?), "Failed to read bls file")?;
+    let info = errmsg(bls_info(reader), "Failed to read bls file")?;
+
+    let reader = errmsg(bl_save::Reader::new(open()?), "Failed to read bls file")?;
+    let converted = errmsg(convert(reader), "Failed to convert bls file")?;
+    let converted_info = brs_info(&converted.write_data);
+
+    Ok(vec![
+        ("Blockland", info.to_string(), info.to_json()),
+        ("Converted", converted_info.to_string(), converted_info.to_json()),
+    ])
+}
+
+fn convert_one(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    args: &Args,
+) -> Result<(), String> {
     let output_path = output_path.as_ref();
+    let converted = read_and_convert(input_path.as_ref(), &args.convert)?;
+
+    if let Some(manifest_path) = &args.manifest_path {
+        let manifest_file = errmsg(File::create(manifest_path), "Failed to create manifest")?;
+        let mut manifest_file = BufWriter::new(manifest_file);
+        errmsg(
+            serde_json::to_writer(&mut manifest_file, &Manifest::from_report(&converted).to_json()),
+            "Failed to write manifest",
+        )?;
+        errmsg(manifest_file.flush(), "Failed to write manifest")?;
+        eprintln!("Wrote manifest to {}", manifest_path);
+    }
+
+    if let Some(obj_path) = &args.obj_path {
+        export_obj(Path::new(obj_path), &converted.write_data)?;
+    }
+
+    if let Some(preview_path) = &args.preview_path {
+        let image = render_preview(
+            &converted.write_data,
+            &converted.failed_positions,
+            &args.preview,
+        );
+        let preview_file = errmsg(File::create(preview_path), "Failed to create preview")?;
+        errmsg(
+            image.write_png(BufWriter::new(preview_file)),
+            "Failed to write preview",
+        )?;
+        eprintln!("Rendered preview to {}", preview_path);
+    }
+
+    let split_options = args.split;
+    let splitting = split_options.tile_size.is_some() || split_options.max_bricks.is_some();
+
+    if let Some(sidecar_path) = &args.sidecar_path {
+        if splitting {
+            return Err(String::from("A sidecar can't be written for split saves"));
+        }
+
+        let sidecar_file = errmsg(File::create(sidecar_path), "Failed to create sidecar")?;
+        let mut sidecar_file = BufWriter::new(sidecar_file);
+        errmsg(
+            serde_json::to_writer_pretty(&mut sidecar_file, &sidecar_json(&converted)),
+            "Failed to write sidecar",
+        )?;
+        errmsg(sidecar_file.flush(), "Failed to write sidecar")?;
+        eprintln!("Wrote sidecar to {}", sidecar_path);
+    }
+
+    if !splitting {
+        return write_brs(output_path, &converted.write_data);
+    }
+
+    if is_stdio(output_path) {
+        return Err(String::from("Split saves can't be written to stdout"));
+    }
+
+    let tiles = split(
+        &converted.write_data,
+        &converted.brick_sources,
+        split_options,
+    );
+    let stem = output_path.file_stem().unwrap_or_default().to_string_lossy();
+
+    for tile in &tiles {
+        let file_name = match tile.part {
+            Some(part) => format!("{}_{}_{}_{}.brs", stem, tile.x, tile.y, part),
+            None => format!("{}_{}_{}.brs", stem, tile.x, tile.y),
+        };
+        eprintln!("  {} ({} bricks)", file_name, tile.write_data.bricks.len());
+        write_brs(&output_path.with_file_name(file_name), &tile.write_data)?;
+    }
+
+    eprintln!("Split into {} saves", tiles.len());
+
+    Ok(())
+}
 
-    let input_file = errmsg(File::open(input_path), "Failed to open bls file")?;
-    let input_file = BufReader::new(input_file);
+/// Converts a bls file (or stdin) and prints the outcome.
+fn read_and_convert(input_path: &Path, options: &ConvertOptions) -> Result<ConvertReport, String> {
+    let input_file: Box<dyn BufRead> = if is_stdio(input_path) {
+        Box::new(io::stdin().lock())
+    } else {
+        let input_file = errmsg(File::open(input_path), "Failed to open bls file")?;
+        Box::new(BufReader::new(input_file))
+    };
     let input_reader = errmsg(bl_save::Reader::new(input_file), "Failed to read bls file")?;
 
-    let mut converted = errmsg(convert(input_reader), "Failed to convert bls file")?;
+    let mut converted = errmsg(
+        convert_with_options(input_reader, options),
+        "Failed to convert bls file",
+    )?;
+
+    if !is_stdio(input_path) {
+        prefix_description(&mut converted, input_path);
+    }
+
+    print_report(
+        &converted.unknown_ui_names,
+        converted.count_success,
+        converted.count_failure,
+        converted.write_data.bricks.len(),
+    );
+
+    if !converted.unknown_music.is_empty() {
+        eprintln!("Music loops without an audio asset:");
+        let mut music: Vec<_> = converted.unknown_music.iter().collect();
+        music.sort_by(|(_, ac), (_, bc)| ac.cmp(bc).reverse());
+        for (music, count) in music {
+            eprintln!("  {:<28} {:>4} bricks", music, count);
+        }
+    }
+
+    if !converted.unknown_vehicles.is_empty() {
+        eprintln!("Vehicles without a prefab:");
+        let mut vehicles: Vec<_> = converted.unknown_vehicles.iter().collect();
+        vehicles.sort_by(|(_, ac), (_, bc)| ac.cmp(bc).reverse());
+        for (vehicle, count) in vehicles {
+            eprintln!("  {:<28} {:>4} spawns", vehicle, count);
+        }
+    }
+
+    if !converted.spawns.is_empty() {
+        let count = |kind| converted.spawns.iter().filter(|s| s.kind == kind).count();
+        eprintln!(
+            "{} spawn points, {} vehicle spawns and {} checkpoints",
+            count(SpawnKind::Player),
+            count(SpawnKind::Vehicle),
+            count(SpawnKind::Checkpoint),
+        );
+    }
+
+    if converted.terrain.refitted > 0 {
+        eprintln!(
+            "{} ModTer bricks turned to fit their neighbours",
+            converted.terrain.refitted
+        );
+    }
+
+    if converted.terrain.merged > 0 {
+        eprintln!(
+            "{} ModTer bricks saved by merging runs",
+            converted.terrain.merged
+        );
+    }
+
+    if converted.size_limits.split > 0 {
+        eprintln!(
+            "{} oversized bricks split into {}",
+            converted.size_limits.split, converted.size_limits.split_into
+        );
+    }
+
+    if !converted.size_limits.oversized.is_empty() {
+        eprintln!("Bricks larger than Brickadia allows:");
+        let mut oversized: Vec<_> = converted.size_limits.oversized.iter().collect();
+        oversized.sort_by(|(_, ac), (_, bc)| ac.cmp(bc).reverse());
+        for (ui_name, count) in oversized {
+            eprintln!("  {:<28} {:>4} bricks", ui_name, count);
+        }
+    }
+
+    if !converted.ground_layer.is_empty() {
+        eprintln!(
+            "{} baseplate bricks in the ground layer",
+            converted.ground_layer.len()
+        );
+    }
+
+    if !converted.volumes.is_empty() {
+        eprintln!(
+            "{} water and zone bricks left out as volumes",
+            converted.volumes.len()
+        );
+    }
+
+    if !converted.untranslated_events.is_empty() {
+        eprintln!("Untranslated events:");
+        for untranslated in &converted.untranslated_events {
+            eprintln!(
+                "  brick {} ({}): {}",
+                untranslated.source, converted.ui_names[untranslated.source], untranslated.event,
+            );
+        }
+    }
+
+    Ok(converted)
+}
+
+/// Writes an OBJ file and its MTL file next to it.
+fn export_obj(obj_path: &Path, write_data: &brs::WriteData) -> Result<(), String> {
+    let mtl_path = obj_path.with_extension("mtl");
+    let mtl_file_name = mtl_path.file_name().unwrap_or_default().to_string_lossy();
+
+    let obj_file = errmsg(File::create(obj_path), "Failed to create OBJ file")?;
+    let mut obj_file = BufWriter::new(obj_file);
+    errmsg(
+        write_obj(write_data, &mut obj_file, Some(&mtl_file_name)),
+        "Failed to write OBJ file",
+    )?;
+    errmsg(obj_file.flush(), "Failed to write OBJ file")?;
+
+    let mtl_file = errmsg(File::create(&mtl_path), "Failed to create MTL file")?;
+    let mut mtl_file = BufWriter::new(mtl_file);
+    errmsg(write_mtl(write_data, &mut mtl_file), "Failed to write MTL file")?;
+    errmsg(mtl_file.flush(), "Failed to write MTL file")?;
+
+    eprintln!("Exported geometry to {}", obj_path.display());
+
+    Ok(())
+}
+
+/// Writes a brs file (or stdout).
+fn write_brs(output_path: &Path, write_data: &brs::WriteData) -> Result<(), String> {
+    let mut output_file: Box<dyn Write> = if is_stdio(output_path) {
+        Box::new(io::stdout().lock())
+    } else {
+        let output_file = errmsg(File::create(output_path), "Failed to create BRS file")?;
+        Box::new(BufWriter::new(output_file))
+    };
+
+    errmsg(
+        brs::write_save(&mut output_file, write_data),
+        "Failed to write BRS file",
+    )?;
+    errmsg(output_file.flush(), "Failed to write BRS file")?;
+
+    Ok(())
+}
+
+fn convert_zip(
+    input_path: &Path,
+    output_path: &Path,
+    options: &ConvertOptions,
+) -> Result<(), String> {
+    let input_file = errmsg(File::open(input_path), "Failed to open zip file")?;
+
+    let mut output_zip = if is_zip(output_path) {
+        let output_file = errmsg(File::create(output_path), "Failed to create zip file")?;
+        Some(zip::ZipWriter::new(BufWriter::new(output_file)))
+    } else {
+        None
+    };
+
+    let report = convert_archive(BufReader::new(input_file), options, |entry_path, mut converted| {
+        eprintln!(
+            "  {}: {} of {} bricks converted",
+            entry_path.display(),
+            converted.count_success,
+            converted.count_success + converted.count_failure,
+        );
+
+        prefix_description(&mut converted, entry_path);
+        let brs_path = entry_path.with_extension("brs");
 
+        match &mut output_zip {
+            Some(output_zip) => {
+                output_zip.start_file(zip_entry_name(&brs_path), Default::default())?;
+                brs::write_save(output_zip, &converted.write_data)
+            }
+            None => {
+                let brs_path = output_path.join(brs_path);
+                if let Some(parent) = brs_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut output_file = BufWriter::new(File::create(brs_path)?);
+                brs::write_save(&mut output_file, &converted.write_data)?;
+                output_file.flush()
+            }
+        }
+    });
+    let report = errmsg(report, "Failed to convert zip file")?;
+
+    if let Some(mut output_zip) = output_zip {
+        errmsg(output_zip.finish(), "Failed to write zip file")?;
+    }
+
+    eprintln!("{} saves converted", report.count_saves);
+
+    if !report.failures.is_empty() {
+        eprintln!("Saves that failed to convert:");
+        for (entry_path, error) in &report.failures {
+            eprintln!("  {}: {}", entry_path.display(), error);
+        }
+    }
+
+    print_report(
+        &report.unknown_ui_names,
+        report.count_success,
+        report.count_failure,
+        report.count_output,
+    );
+
+    Ok(())
+}
+
+fn prefix_description(converted: &mut ConvertReport, input_path: &Path) {
     if let Some(file_name) = input_path.file_name() {
         let mut prefix = format!(
             "Converted from {} with bls2brs.",
@@ -71,57 +633,341 @@ fn convert_one(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> R
 
         converted.write_data.description.insert_str(0, &prefix);
     }
+}
 
-    if !converted.unknown_ui_names.is_empty() {
-        println!("Unknown bricks:");
-        let mut ui_names: Vec<_> = converted.unknown_ui_names.into_iter().collect();
+fn print_report(
+    unknown_ui_names: &HashMap<String, usize>,
+    count_success: usize,
+    count_failure: usize,
+    count_output: usize,
+) {
+    if !unknown_ui_names.is_empty() {
+        eprintln!("Unknown bricks:");
+        let mut ui_names: Vec<_> = unknown_ui_names.iter().collect();
         ui_names.sort_by(|(_, ac), (_, bc)| ac.cmp(bc).reverse());
         for (ui_name, count) in ui_names {
             let ui_name = if ui_name != ui_name.trim() {
                 format!("{:?}", ui_name)
             } else {
-                ui_name
+                ui_name.clone()
             };
-            println!("  {:<28} {:>4} bricks", ui_name, count);
+            eprintln!("  {:<28} {:>4} bricks", ui_name, count);
         }
     }
 
-    if converted.count_failure > 0 {
-        println!("{} bricks failed to convert", converted.count_failure);
+    if count_failure > 0 {
+        eprintln!("{} bricks failed to convert", count_failure);
     }
 
-    println!(
+    eprintln!(
         "{} of {} bricks converted successfully to {} bricks",
-        converted.count_success,
-        converted.count_success + converted.count_failure,
-        converted.write_data.bricks.len(),
+        count_success,
+        count_success + count_failure,
+        count_output,
     );
+}
 
-    let mut output_file = errmsg(File::create(output_path), "Failed to create BRS file")?;
-
-    errmsg(
-        brs::write_save(&mut output_file, &converted.write_data),
-        "Failed to write BRS file",
-    )?;
+/// Zip entries always use `/` as the separator.
+fn zip_entry_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-    Ok(())
+enum Command {
+    Convert,
+    Merge,
+    Info,
+    Diff,
 }
 
 struct Args {
+    command: Command,
     input_paths: Vec<String>,
+    /// Brick offsets for merged inputs, parallel to `input_paths`.
+    offsets: Vec<(i32, i32, i32)>,
+    output_path: Option<String>,
+    split: SplitOptions,
+    obj_path: Option<String>,
+    preview_path: Option<String>,
+    preview: PreviewOptions,
+    manifest_path: Option<String>,
+    sidecar_path: Option<String>,
+    convert: ConvertOptions,
+    json: bool,
 }
 
-fn parse_args() -> Result<Args, ()> {
-    let mut args = std::env::args();
-    args.next().unwrap();
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1).peekable();
+
+    let command = match args.peek().map(String::as_str) {
+        Some("merge") => {
+            args.next();
+            Command::Merge
+        }
+        Some("info") => {
+            args.next();
+            Command::Info
+        }
+        Some("diff") => {
+            args.next();
+            Command::Diff
+        }
+        _ => Command::Convert,
+    };
+
+    let mut input_paths = Vec::new();
+    let mut offsets = Vec::new();
+    let mut output_path = None;
+    let mut next_offset = None;
+    let mut split = SplitOptions::default();
+    let mut obj_path = None;
+    let mut preview_path = None;
+    let mut preview = PreviewOptions::default();
+    let mut manifest_path = None;
+    let mut sidecar_path = None;
+    let mut convert = ConvertOptions::default();
+    let mut json = false;
 
-    let input_paths: Vec<_> = args.collect();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path (or - for stdout).", arg))?;
+                output_path = Some(path);
+            }
+            "--offset" if matches!(command, Command::Merge) => {
+                let offset = args
+                    .next()
+                    .and_then(|s| parse_offset(&s))
+                    .ok_or_else(|| format!("Error: {} requires an offset like 640,0,0.", arg))?;
+                next_offset = Some(offset);
+            }
+            "--tile-size" if matches!(command, Command::Convert) => {
+                let tile_size = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|size| *size > 0)
+                    .ok_or_else(|| format!("Error: {} requires a size in Brickadia units.", arg))?;
+                split.tile_size = Some(tile_size);
+            }
+            "--max-bricks" if matches!(command, Command::Convert) => {
+                let max_bricks = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| format!("Error: {} requires a brick count.", arg))?;
+                split.max_bricks = Some(max_bricks);
+            }
+            "--obj" if matches!(command, Command::Convert) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                obj_path = Some(path);
+            }
+            "--preview" if matches!(command, Command::Convert) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                preview_path = Some(path);
+            }
+            "--preview-side" if matches!(command, Command::Convert) => {
+                preview.side_view = true;
+            }
+            "--preview-size" if matches!(command, Command::Convert) => {
+                let max_size = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|size| *size > 0)
+                    .ok_or_else(|| format!("Error: {} requires a size in pixels.", arg))?;
+                if max_size > MAX_PREVIEW_SIZE {
+                    return Err(format!(
+                        "Error: {} can be at most {} pixels.",
+                        arg, MAX_PREVIEW_SIZE
+                    ));
+                }
+                preview.max_size = max_size;
+            }
+            "--highlight" if matches!(command, Command::Convert) => {
+                preview.highlight = args
+                    .next()
+                    .and_then(|s| parse_color(&s))
+                    .ok_or_else(|| format!("Error: {} requires a color like ff00ff.", arg))?;
+            }
+            "--manifest" if matches!(command, Command::Convert) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                manifest_path = Some(path);
+            }
+            "--sidecar" if matches!(command, Command::Convert) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                sidecar_path = Some(path);
+            }
+            "--music-table" if !matches!(command, Command::Info) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                convert.music = load_table(Path::new(&path))?;
+            }
+            "--vehicle-table" if !matches!(command, Command::Info) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                convert.vehicles = load_table(Path::new(&path))?;
+            }
+            "--road-palette" if !matches!(command, Command::Info) => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires a path.", arg))?;
+                convert.road_palette = load_road_palette(Path::new(&path))?;
+            }
+            "--terrain" if !matches!(command, Command::Info) => {
+                convert.terrain.get_or_insert_with(TerrainOptions::default);
+            }
+            "--terrain-merge" if !matches!(command, Command::Info) => {
+                convert
+                    .terrain
+                    .get_or_insert_with(TerrainOptions::default)
+                    .merge = true;
+            }
+            "--octo-style" if !matches!(command, Command::Info) => {
+                convert.octo_style = args
+                    .next()
+                    .and_then(|s| OctoStyle::parse(&s))
+                    .ok_or_else(|| format!("Error: {} requires assets or microbricks.", arg))?;
+            }
+            "--baseplate-asset" if !matches!(command, Command::Info) => {
+                convert.baseplates.asset = args
+                    .next()
+                    .and_then(|s| BaseplateAsset::parse(&s))
+                    .ok_or_else(|| format!("Error: {} requires studded or smooth.", arg))?;
+            }
+            "--baseplate-chunk" if !matches!(command, Command::Info) => {
+                let max_extent = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|size| *size > 0)
+                    .ok_or_else(|| format!("Error: {} requires a size in Brickadia units.", arg))?;
+                convert.baseplates.max_extent = Some(max_extent);
+            }
+            "--whole-baseplates" if !matches!(command, Command::Info) => {
+                convert.baseplates.max_extent = None;
+            }
+            "--ground-layer" if matches!(command, Command::Convert) => {
+                convert.baseplates.ground_layer = true;
+            }
+            "--max-extent" if !matches!(command, Command::Info) => {
+                convert.size_limits.max_extent = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|size| *size > 0)
+                    .ok_or_else(|| format!("Error: {} requires a size in Brickadia units.", arg))?;
+            }
+            "--keep-oversized" if !matches!(command, Command::Info) => {
+                convert.size_limits.split = false;
+            }
+            "--json" if matches!(command, Command::Info | Command::Diff) => {
+                json = true;
+            }
+            _ => {
+                input_paths.push(arg);
+                offsets.push(next_offset.take().unwrap_or_default());
+            }
+        }
+    }
 
     if input_paths.is_empty() {
-        return Err(());
+        return Err(String::from("Error: No bls files given. Drag them onto this program's executable file. (Not this window! This is just an error message, not the program itself.)"));
     }
 
-    Ok(Args { input_paths })
+    Ok(Args {
+        command,
+        input_paths,
+        offsets,
+        output_path,
+        split,
+        obj_path,
+        preview_path,
+        preview,
+        manifest_path,
+        sidecar_path,
+        convert,
+        json,
+    })
+}
+
+/// Reads a JSON object of strings, like `{ "musicData_Ambient_Drone": "BA_Drone" }`.
+fn load_table(path: &Path) -> Result<HashMap<String, String>, String> {
+    let file = errmsg(File::open(path), "Failed to open table")?;
+    errmsg(
+        serde_json::from_reader(BufReader::new(file)),
+        &format!("Failed to read table {}", path.display()),
+    )
+}
+
+/// Reads road colors like `{ "asphalt": "0b0b0b", "stripe": "fefee8" }`.
+/// Colors that aren't given keep their default.
+fn load_road_palette(path: &Path) -> Result<RoadPalette, String> {
+    let mut palette = RoadPalette::default();
+    for (paint, color) in load_table(path)? {
+        let color = parse_color(&color).ok_or_else(|| {
+            format!("Error: Road color {} should be a color like ff00ff, not {}.", paint, color)
+        })?;
+        match paint.as_str() {
+            "asphalt" => palette.asphalt = color,
+            "stripe" => palette.stripe = color,
+            "center_line" => palette.center_line = color,
+            _ => {
+                return Err(format!(
+                    "Error: Unknown road color {}, expected asphalt, stripe or center_line.",
+                    paint
+                ))
+            }
+        }
+    }
+    Ok(palette)
+}
+
+/// Parses `x,y,z` in Brickadia units.
+fn parse_offset(s: &str) -> Option<(i32, i32, i32)> {
+    let mut parts = s.split(',').map(|p| p.trim().parse().ok());
+    let offset = (parts.next()??, parts.next()??, parts.next()??);
+    match parts.next() {
+        None => Some(offset),
+        Some(_) => None,
+    }
+}
+
+/// Parses a hex color like `ff00ff`, with an optional leading `#`.
+fn parse_color(s: &str) -> Option<brs::Color> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if s.len() != 6 {
+        return None;
+    }
+    let rgb = u32::from_str_radix(s, 16).ok()?;
+    Some(brs::Color::from_rgba(
+        (rgb >> 16) as u8,
+        (rgb >> 8) as u8,
+        rgb as u8,
+        255,
+    ))
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO_PATH)
+}
+
+fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
 }
 
 fn errmsg<T, E: std::fmt::Display>(r: Result<T, E>, message_prefix: &str) -> Result<T, String> {
@@ -1,6 +1,13 @@
 #![allow(clippy::identity_op)]
 
-use crate::types::{BrickDesc, BrickMapping};
+use crate::{
+    doors::{self, DoorStyle},
+    lattice::{self, LatticePlate, LatticeShape},
+    octo::{octo_t, Octo, OctoAxis},
+    roads,
+    types::{BrickDesc, BrickMapping},
+    volumes,
+};
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 use std::collections::{HashMap, HashSet};
@@ -17,21 +24,13 @@ lazy_static! {
 
     static ref WINDOW_COLOR: brs::Color = brs::Color::from_rgba(150, 150, 150, 180);
 
-    static ref BRICK_ROAD_LANE: BrickDesc = BrickDesc::new("PB_DefaultMicroBrick")
-        .color_override(brs::Color::from_rgba(11, 11, 11, 255));
-    static ref BRICK_ROAD_STRIPE: BrickDesc = BrickDesc::new("PB_DefaultMicroBrick")
-        .color_override(brs::Color::from_rgba(254, 254, 232, 255));
-    static ref BRICK_ROAD_CENTER: BrickDesc = BrickDesc::new("PB_DefaultMicroBrick")
-        .color_override(brs::Color::from_rgba(250, 200, 10, 255));
-    static ref GENERIC_DOOR: BrickMapping = vec![
-        //frame
+    static ref DOOR_FRAME: BrickMapping = vec![
         BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 1)).offset((0, 0, -35)),
         BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 1)).offset((0, 0, 35)),
         BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 34)).offset((0, 19, 0)),
         BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 34)).offset((0, -19, 0)),
-        //door
-        BrickDesc::new("PB_DefaultMicroBrick").size((18, 1, 34)).nocollide(),
-        //handle
+    ];
+    static ref DOOR_HANDLE: BrickMapping = vec![
         BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 3)).offset((2, 12, 0)).nocollide(),
         BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 3)).offset((-2, 12, 0)).nocollide(),
     ];
@@ -61,7 +60,6 @@ lazy_static! {
         "2x2x2 Cone" => BrickDesc::new("B_2x2_Cone"),
         "1x1 Round" => BrickDesc::new("B_1x1_Round"),
         "1x1 Round Horiz" => BrickDesc::new("B_1x1_Round").rotate_by_direction(),
-        "1x1 Octo Plate" => BrickDesc::new("B_1x1F_Octo"),
         "1x1F Round" => BrickDesc::new("B_1x1F_Round"),
         "1x1f Round Horiz" => BrickDesc::new("B_1x1F_Round").rotate_by_direction().rotation_offset(0),
         "2x2 Round" => BrickDesc::new("B_2x2_Round"),
@@ -69,8 +67,6 @@ lazy_static! {
         "Pine Tree" => BrickDesc::new("B_Pine_Tree").offset((0, 0, -6)),
         "2x2 Bush" => BrickDesc::new("B_Bush").offset((0, 0, -14)),
         "2x2 Corner" => BrickDesc::new("B_2x2_Corner").rotation_offset(0),
-        "2x2 Octo Plate" => BrickDesc::new("B_2x2F_Octo"),
-        "8x8 Grill" => BrickDesc::new("B_8x8_Lattice_Plate"),
         "1x4x2 Picket" => BrickDesc::new("B_Picket_Fence"),
 
         //==================================================================================
@@ -97,8 +93,6 @@ lazy_static! {
         "2x2x1 Octo Cone" => BrickDesc::new("B_2x2_Round"),
         "Gravestone" => BrickDesc::new("B_Gravestone"),
         "Pumpkin" => BrickDesc::new("B_Pumpkin").offset((0, 0, -3)),
-        "House Door" => GENERIC_DOOR.clone(),
-        "Plain Door" => GENERIC_DOOR.clone(),
         "1x1 Bamboo" => BrickDesc::new("B_1x1_Round").offset((-10, -10, 0)),
         "4x3 Leaves" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 3, 2)),
@@ -106,12 +100,6 @@ lazy_static! {
             BrickDesc::new("B_1x1F_Octo").offset((0, -15, 0)),
         ],
 
-        "2x2 Octo" => vec![
-            BrickDesc::new("B_2x2F_Octo").offset((0, 0, -4)),
-            BrickDesc::new("B_2x2F_Octo"),
-            BrickDesc::new("B_2x2F_Octo").offset((0, 0, 4)),
-        ],
-
         "Castle Wall" => vec![
             BrickDesc::new("PB_DefaultSmoothTile").size((15, 5, 18)).offset((0, 0, -18)),
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 10)).offset((0, 10, 10)),
@@ -127,7 +115,7 @@ lazy_static! {
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 27)).rotation_offset(0).offset((0, 19, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 27)).rotation_offset(0).offset((0, -19, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 18, 27)).rotation_offset(0).offset((-4, 0, 1))
-                .color_override(WINDOW_COLOR.clone()),
+                .color_override(*WINDOW_COLOR),
         ],
 
         "1x4x2 Bars" => vec![
@@ -305,98 +293,6 @@ lazy_static! {
                 .direction_override(ZNegative),
         ],
 
-        "32x32 Road" => vec![
-            // left and right sidewalks
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 32*5, 2)).offset((0, -115, 0)),
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 32*5, 2)).offset((0, 115, 0)),
-            // left and right stripes
-            BRICK_ROAD_STRIPE.clone().size((4, 32*5, 2)).offset((0, -66, 0)),
-            BRICK_ROAD_STRIPE.clone().size((4, 32*5, 2)).offset((0, 66, 0)),
-            // lanes
-            BRICK_ROAD_LANE.clone().size((29, 32*5, 2)).offset((0, -33, 0)),
-            BRICK_ROAD_LANE.clone().size((29, 32*5, 2)).offset((0, 33, 0)),
-            // center strip
-            BRICK_ROAD_CENTER.clone().size((4, 32*5, 2))
-        ],
-
-        // Orientations are relative to this camera position on Beta City:
-        // 39.5712 0.0598862 14.5026 0.999998 -0.0007625 0.00180403 0.799784
-        "32x32 Road T" => vec![
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 32*5, 2)).offset((0, -115, 0)), // top
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((-115, 115, 0)), // bottom left
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((115, 115, 0)), // bottom right
-            BRICK_ROAD_STRIPE.clone().size((4, 32*5, 2)).offset((0, -66, 0)), // straight top
-            BRICK_ROAD_STRIPE.clone().size((4, 32*5, 2)).offset((0, 66, 0)), // straight bottom
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).rotation_offset(0).offset((-66, 23*5, 0)), // bottom left
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).rotation_offset(0).offset((66, 23*5, 0)), // bottom right
-            BRICK_ROAD_LANE.clone().size((29, 32*5, 2)).offset((0, -33, 0)), // straight top
-            BRICK_ROAD_LANE.clone().size((29, 32*5, 2)).offset((0, 33, 0)), // straight bottom
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((-33, 23*5, 0)), // bottom left
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((33, 23*5, 0)), // bottom right
-            BRICK_ROAD_LANE.clone().size((2, 4, 2)).offset((0, 72, 0)),
-            BRICK_ROAD_CENTER.clone().size((4, 32*5, 2)),
-            BRICK_ROAD_CENTER.clone().size((43, 4, 2)).offset((0, 117, 0)),
-        ],
-
-        // Orientations are relative to this camera position on Beta City:
-        // -56.5 -35 4 0 0 1 3.14159
-        "32x32 Road X" => vec![
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((-23*5, -23*5, 0)), // top left
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((23*5, -23*5, 0)), // top right
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((-23*5, 23*5, 0)), // bottom left
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((23*5, 23*5, 0)), // bottom right
-            BRICK_ROAD_STRIPE.clone().size((4, 160, 2)).rotation_offset(0).offset((-66, 0, 0)), // inner bottom
-            BRICK_ROAD_STRIPE.clone().size((4, 160, 2)).rotation_offset(0).offset((66, 0, 0)), // inner top
-            BRICK_ROAD_STRIPE.clone().size((4, 62, 2)).offset((0, -66, 0)), // inner left
-            BRICK_ROAD_STRIPE.clone().size((4, 62, 2)).offset((0, 66, 0)), // inner right
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).offset((-23*5, -66, 0)), // bottom left
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).offset((-23*5, 66, 0)), // bottom right
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).offset((23*5, -66, 0)), // top left
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).offset((23*5, 66, 0)), // top right
-            BRICK_ROAD_LANE.clone().size((62, 62, 2)), // inner
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((-33, 23*5, 0)), // right bottom
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((33, 23*5, 0)), // right top
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((-33, -23*5, 0)), // left bottom
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).rotation_offset(0).offset((33, -23*5, 0)), // left top
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).offset((-23*5, -33, 0)), // bottom left
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).offset((-23*5, 33, 0)), // bottom right
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).offset((23*5, -33, 0)), // top left
-            BRICK_ROAD_LANE.clone().size((29, 9*5, 2)).offset((23*5, 33, 0)), // top right
-            BRICK_ROAD_LANE.clone().size((2, 4, 2)).offset((0, 72, 0)),
-            BRICK_ROAD_LANE.clone().size((2, 4, 2)).offset((0, -72, 0)),
-            BRICK_ROAD_LANE.clone().size((4, 2, 2)).offset((72, 0, 0)),
-            BRICK_ROAD_LANE.clone().size((4, 2, 2)).offset((-72, 0, 0)),
-            BRICK_ROAD_CENTER.clone().size((43, 4, 2)).offset((0, 117, 0)),
-            BRICK_ROAD_CENTER.clone().size((43, 4, 2)).offset((0, -117, 0)),
-            BRICK_ROAD_CENTER.clone().size((4, 43, 2)).offset((117, 0, 0)),
-            BRICK_ROAD_CENTER.clone().size((4, 43, 2)).offset((-117, 0, 0)),
-        ],
-
-        // Orientations are relative to this camera position on Beta City:
-        // -25.9168 -110.523 12.5993 0.996034 0.0289472 -0.0841301 0.665224
-        "32x32 Road C" => vec![
-            // sidewalks
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((-115, 115, 0)), // top left
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 9*5, 2)).offset((115, -115, 0)), // bottom right
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 23*5, 2)).rotation_offset(0).offset((115, 45, 0)), // bottom left
-            BrickDesc::new("PB_DefaultBrick").size((9*5, 23*5, 2)).offset((-45, -115, 0)), // top right
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).offset((-115, 66, 0)), // inner right
-            BRICK_ROAD_STRIPE.clone().size((4, 9*5, 2)).rotation_offset(0).offset((-66, 115, 0)), // inner bottom
-            BRICK_ROAD_STRIPE.clone().size((4, 111, 2)).offset((-49, -66, 0)), // top right
-            BRICK_ROAD_STRIPE.clone().size((4, 111, 2)).rotation_offset(0).offset((66, 49, 0)), // bottom left
-            BRICK_ROAD_STRIPE.clone().size((4, 4, 2)).offset((66, -66, 0)), // bottom right
-            BRICK_ROAD_STRIPE.clone().size((4, 4, 2)).rotation_offset(0).offset((-66, 66, 0)), // inner bottom right
-            BRICK_ROAD_LANE.clone().size((29, 49, 2)).offset((-111, 33, 0)), // top left
-            BRICK_ROAD_LANE.clone().size((29, 82, 2)).offset((-78, -33, 0)), // top right
-            BRICK_ROAD_LANE.clone().size((29, 82, 2)).rotation_offset(0).offset((33, 78, 0)), // bottom left
-            BRICK_ROAD_LANE.clone().size((29, 49, 2)).rotation_offset(0).offset((-33, 111, 0)), // left top
-            BRICK_ROAD_LANE.clone().size((29, 29, 2)).offset((-33, 33, 0)), // inner top left
-            BRICK_ROAD_LANE.clone().size((29, 29, 2)).offset((33, -33, 0)), // inner bottom right
-            BRICK_ROAD_CENTER.clone().size((4, 4, 2)),
-            BRICK_ROAD_CENTER.clone().size((4, 78, 2)).offset((-82, 0, 0)),
-            BRICK_ROAD_CENTER.clone().size((78, 4, 2)).offset((0, 82, 0)),
-        ],
-
         //==================================================================================
         // Brick_1RandomPack by FART and King Tinks (One Random Brick Pack)
         //==================================================================================
@@ -419,13 +315,8 @@ lazy_static! {
         // 1RandomBrickPack Correct Octo Mappings
         "2x2x2 Octo Elbow" => BrickDesc::new("B_2x_Octo_90Deg"),
         "2x2x2 Octo - Elbow" => BrickDesc::new("B_2x_Octo_90Deg").direction_override(ZNegative).rotation_offset(3),
-        "2x2x2 Octo T Vert" => BrickDesc::new("B_2x_Octo_T"),
         "2x2x2 Octo Elbow Horz" => BrickDesc::new("B_2x_Octo_90Deg").direction_override(XPositive),
-        "2x2x2 Octo T Horz" => BrickDesc::new("B_2x_Octo_T").direction_override(YNegative),
-        "2x2x2 Octo T" => BrickDesc::new("B_2x_Octo_T").direction_override(YNegative).rotation_offset(2),
-        "2x2x2 Octo T inv" => BrickDesc::new("B_2x_Octo_T").direction_override(YNegative).rotation_offset(0),
         "1x2 Octo Plate90" => BrickDesc::new("B_2x2F_Octo").rotate_by_direction().rotation_offset(1).offset((3, 0, 0)),
-        "2x2 Octo Brick90" => BrickDesc::new("B_2x_Octo").rotate_by_direction().rotation_offset(1),
         "2x2f Print 90" => BrickDesc::new("PB_DefaultSmoothTile").size((10, 10, 2)).offset((3, 0, 0)).direction_override(YPositive),
         "2x2f Round Ceiling" => BrickDesc::new("PB_DefaultPole").size((10, 10, 2)),
         "2x2f Round Print 90" => BrickDesc::new("PB_DefaultPole").size((10, 10, 2)).offset((3, 0, 0)).direction_override(YNegative),
@@ -532,39 +423,11 @@ lazy_static! {
             BrickDesc::new("PB_DefaultMicroWedge").size((1, 5, 2)).offset((9, 5, 0)).rotation_offset(0),
         ],
 
-        // TODO: Revisit if Octo bricks become procedural
-        "1x1 Octo" => vec![
-            BrickDesc::new("B_1x1F_Octo"),
-            BrickDesc::new("B_1x1F_Octo").offset((0, 0, 4)),
-            BrickDesc::new("B_1x1F_Octo").offset((0, 0, -4)),
-        ],
-        "1x1x2 Octo" => vec![
-            BrickDesc::new("B_1x_Octo").offset((0, 0, -7)),
-            BrickDesc::new("B_1x_Octo").offset((0, 0, 7)),
-            BrickDesc::new("B_1x1F_Octo")
-        ],
-        "2x2x2 Octo" => vec![
-            BrickDesc::new("B_2x_Octo").offset((0, 0, -2)),
-            BrickDesc::new("B_2x2F_Octo").offset((0, 0, 10))
-        ],
+        // Plain octos and octo plus bricks are built procedurally
         "2x2x2 Octo Cone Inv" => vec![
             BrickDesc::new("B_2x_Octo_Cone").offset((0, 0, -2)).direction_override(ZNegative),
             BrickDesc::new("B_2x2F_Octo").offset((0, 0, 10))
         ],
-        "2x2x2 Octo Plus Vert" => BrickDesc::new("PB_DefaultStudded").size((10, 10, 10)),
-        "2x2x2 Octo Plus Horz" => BrickDesc::new("PB_DefaultStudded").size((10, 10, 10)),
-        "2x2x2 Octo Plus Plus" => BrickDesc::new("PB_DefaultStudded").size((10, 10, 10)),
-        "1x2 Octo Brick90" => vec![
-            BrickDesc::new("B_2x2F_Octo").rotate_by_direction().rotation_offset(3).offset((3, 0, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((10, 1, 4)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 3)).offset((0, 0, 7)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 3)).offset((0, 0, -7)),
-            BrickDesc::new("PB_DefaultMicroWedge").size((3, 1, 3)).offset((0, -7, -7)).microwedge_rotate(true).rotation_offset(1).inverted_wedge_rotate(true).inverted_modter_rotate(true),
-            BrickDesc::new("PB_DefaultMicroWedge").size((3, 1, 3)).offset((0, 7, -7)).microwedge_rotate(true).rotation_offset(3).inverted_wedge_rotate(true).inverted_modter_rotate(true),
-            BrickDesc::new("PB_DefaultMicroWedge").size((3, 1, 3)).offset((0, -7, 7)).microwedge_rotate(true).rotation_offset(3),
-            BrickDesc::new("PB_DefaultMicroWedge").size((3, 1, 3)).offset((0, 7, 7)).microwedge_rotate(true).rotation_offset(1),
-            BrickDesc::new("B_2x2F_Octo").rotate_by_direction().rotation_offset(3).offset((-3, 0, 0)),
-        ],
         "2x3x2 Octo Offset" => vec![
             BrickDesc::new("B_2x2F_Octo").offset((0, -5, -10)),
             BrickDesc::new("B_2x2F_Octo").offset((0, -3, -6)),
@@ -923,70 +786,70 @@ lazy_static! {
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 1)).offset((0, 0, 5)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 3)).offset((0, 4, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 3)).offset((0, -4, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 3)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 3)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x1x2 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 2)).offset((0, 0, -10)),
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 1)).offset((0, 0, 11)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, 4, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, -4, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 9)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 9)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x1x3 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 2)).offset((0, 0, -16)),
             BrickDesc::new("PB_DefaultMicroBrick").size((5, 5, 1)).offset((0, 0, 17)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, 4, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, -4, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 15)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 15)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x2x1 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 2)).offset((0, 0, -4)),
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 1)).offset((0, 0, 5)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 3)).offset((0, 9, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 3)).offset((0, -9, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 3)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 3)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x2x2 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 2)).offset((0, 0, -10)),
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 1)).offset((0, 0, 11)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, 9, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, -9, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 9)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 9)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x2x3 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 2)).offset((0, 0, -16)),
             BrickDesc::new("PB_DefaultMicroBrick").size((10, 5, 1)).offset((0, 0, 17)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, 9, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, -9, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 15)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((8, 1, 15)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x3x2 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((15, 5, 2)).offset((0, 0, -10)),
             BrickDesc::new("PB_DefaultMicroBrick").size((15, 5, 1)).offset((0, 0, 11)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, 14, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, -14, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((13, 1, 9)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((13, 1, 9)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x3x3 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((15, 5, 2)).offset((0, 0, -16)),
             BrickDesc::new("PB_DefaultMicroBrick").size((15, 5, 1)).offset((0, 0, 17)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, 14, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, -14, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((13, 1, 15)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((13, 1, 15)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x4x2 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 2)).offset((0, 0, -10)),
             BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 1)).offset((0, 0, 11)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, 19, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 9)).offset((0, -19, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((18, 1, 9)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((18, 1, 9)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
         "1x4x3 Window" => vec![
             BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 2)).offset((0, 0, -16)),
             BrickDesc::new("PB_DefaultMicroBrick").size((20, 5, 1)).offset((0, 0, 17)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, 19, 1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 5, 15)).offset((0, -19, 1)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((18, 1, 15)).offset((-4, 0, 1)).color_override(WINDOW_COLOR.clone()),
+            BrickDesc::new("PB_DefaultMicroBrick").size((18, 1, 15)).offset((-4, 0, 1)).color_override(*WINDOW_COLOR),
         ],
 
         //==================================================================================
@@ -1116,74 +979,6 @@ lazy_static! {
             BrickDesc::new("PB_DefaultBrick").size((20, 20, 26)).offset((0, 0, -76)).non_priority(true),
         ],
 
-        //==================================================================================
-        // Brick_SmallBricks by Kris (Based off of Vertical Print Plate Pack by Emil)
-        //==================================================================================
-        "0.75x0.75F" => BrickDesc::new("PB_DefaultMicroBrick").size((4, 4, 2)).offset((1, 1, 0)),
-        "0.75x0.75F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((4, 4, 2)),
-        "0.75x1F" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 4, 2)).offset((1, 0, 0)),
-        "0.75x1F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 4, 2)),
-        "0.75x2F" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 4, 2)).offset((1, 0, 0)),
-        "0.75x2F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 4, 2)).offset((1, 0, 0)).non_priority(true),
-        "0.75x1" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 4, 6)).offset((1, 0, 0)),
-        "0.75x2" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 4, 6)).offset((1, 0, 0)),
-        "0.5x0.5 Edge" => BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 6)).offset((3, 3, 0)),
-        "0.5x0.5 Edge No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 6)).offset((3, 3, 0)).non_priority(true),
-        "0.5x0.5F" => BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 2)).offset((3, 3, 0)),
-        "0.5x0.5F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 2)),
-        "0.5x0.5F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 2)).offset((3, 3, 0)).non_priority(true),
-        "0.5x1F" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 2, 2)).offset((3, 0, 0)),
-        "0.50x1F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 2, 2)),
-        "0.5x1F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 2, 2)).offset((3, 0, 0)).non_priority(true),
-        "0.5x2F" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 2, 2)).offset((3, 0, 0)),
-        "0.5x2F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 2, 2)).offset((3, 0, 0)).non_priority(true),
-        "0.5x1" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 2, 6)).offset((3, 0, 0)),
-        "0.5x1 No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 2, 6)).offset((3, 0, 0)).non_priority(true),
-        "0.5x2" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 2, 6)).offset((3, 0, 0)),
-        "0.5x2 No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 2, 6)).offset((3, 0, 0)).non_priority(true),
-        "0.25x0.25 Edge" => BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 6)).offset((4, 4, 0)),
-        "0.25x0.25 Edge No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 6)).offset((4, 4, 0)).non_priority(true),
-        "0.25x0.25F" => BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 2)).offset((4, 4, 0)),
-        "0.25x0.25F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 2)),
-        "0.25x0.25F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 2)).offset((4, 4, 0)).non_priority(true),
-        "0.25x1F" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 2)).offset((4, 0, 0)),
-        "0.25x1F Centered" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 2)),
-        "0.25x1F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 2)).offset((4, 0, 0)).non_priority(true),
-        "0.25x1" => BrickDesc::new("PB_DefaultMicroBrick").size((5, 1, 6)).offset((4, 0, 0)),
-        "0.25x2F" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 1, 2)).offset((4, 0, 0)),
-        "0.25x2F No Overlap" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 1, 2)).offset((4, 0, 0)).non_priority(true),
-        "0.25x2" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 1, 6)).offset((4, 0, 0)),
-        "0.25x0.25F Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 2)).offset((4, -4, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 4, 2)).offset((-1, -4, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 2)).offset((4, 1, 0)),
-        ],
-        "0.25x0.25 Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 6)).offset((4, -4, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 4, 6)).offset((-1, -4, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 6)).offset((4, 1, 0)),
-        ],
-        "0.5x0.5F Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 2)).offset((3, -3, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((2, 3, 2)).offset((-2, -3, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((3, 2, 2)).offset((3, 2, 0)),
-        ],
-        "0.5x0.5 Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((2, 2, 6)).offset((3, -3, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((2, 3, 6)).offset((-2, -3, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((3, 2, 6)).offset((3, 2, 0)),
-        ],
-        "0.75x0.75F Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 4, 2)).offset((1, -1, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 2)).offset((-4, -1, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 4, 2)).offset((1, 4, 0)),
-        ],
-        "0.75x0.75 Corner" => vec![
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 4, 6)).offset((1, -1, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((4, 1, 6)).offset((-4, -1, 0)),
-            BrickDesc::new("PB_DefaultMicroBrick").size((1, 4, 6)).offset((1, 4, 0)),
-        ],
-
         // Optimized Cubes
         "2x Cube 4x V" => BrickDesc::new("PB_DefaultMicroBrick").size((10, 10, 40)),
         "4x Cube 4h" => BrickDesc::new("PB_DefaultBrick").size((80, 80, 20)),
@@ -1242,7 +1037,7 @@ lazy_static! {
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 1)).offset((2, -9, -1)),
             BrickDesc::new("PB_DefaultMicroBrick").size((1, 1, 1)).offset((-2, -9, -1)),
         ],
-        // TODO: Grill Corner
+        "Grill Corner" => lattice::grill_corner(),
 
         //==================================================================================
         // Brick_ExtraRamps by Tophius (An assorment of extra LEGO-inspired ramps)
@@ -1502,7 +1297,7 @@ lazy_static! {
     ];
 
     pub static ref BRICK_MAP_REGEX: Vec<(Regex, RegexHandler)> = brick_map_regex![
-        // TODO: Consider trying to handle fractional sizes that sometimes occur
+        // Fractional sizes are handled by the fractional pattern below
         // TODO: Remove (?: Print)? when prints exist
         r"^(\d+)x(\d+)(?:x(\d+)|([Ff])|([Hh]))?( Print)?( Ceiling)?$" => |captures, from| {
             let width: u32 = captures.get(1).unwrap().as_str().parse().ok()?;
@@ -1536,6 +1331,257 @@ lazy_static! {
                 .direction_override(dir)])
         },
 
+        //==================================================================================
+        // Doors, closed or left open. Only the base game doors, not add-ons like Brick_Doors
+        //==================================================================================
+        r"^(Plain|House|Glass|Jail|Castle|Bathroom Stall) Door(?: Open CC?W)?$" => |captures, _| {
+            let door = doors::parse_door(captures.get(0).unwrap().as_str())?;
+            let panel = BrickDesc::new("PB_DefaultMicroBrick").nocollide();
+
+            // The panel comes first so the door component goes on it
+            let parts: BrickMapping = match door.style {
+                DoorStyle::Plain => {
+                    let mut parts = vec![panel.size((18, 1, 34))];
+                    parts.extend(DOOR_HANDLE.iter().cloned());
+                    parts
+                }
+                DoorStyle::House => {
+                    let mut parts = vec![
+                        panel.clone().size((18, 1, 20)).offset((0, 0, -14)),
+                        panel.clone().size((12, 1, 8)).offset((0, 0, 14)).color_override(*WINDOW_COLOR),
+                        panel.clone().size((3, 1, 8)).offset((0, 15, 14)),
+                        panel.clone().size((3, 1, 8)).offset((0, -15, 14)),
+                        panel.size((18, 1, 6)).offset((0, 0, 28)),
+                    ];
+                    parts.extend(DOOR_HANDLE.iter().cloned());
+                    parts
+                }
+                DoorStyle::Glass => {
+                    let mut parts = vec![panel.size((18, 1, 34)).color_override(*WINDOW_COLOR)];
+                    parts.extend(DOOR_HANDLE.iter().cloned());
+                    parts
+                }
+                DoorStyle::Jail => {
+                    let mut parts: BrickMapping = [-33, 0, 33]
+                        .iter()
+                        .map(|&z| panel.clone().size((18, 1, 1)).offset((0, 0, z)))
+                        .collect();
+                    parts.extend([-15, -9, -3, 3, 9, 15].iter().map(|&y| {
+                        panel.clone().size((1, 1, 34)).offset((0, y, 0))
+                    }));
+                    parts
+                }
+                DoorStyle::Castle => {
+                    let strap = panel.clone().size((18, 1, 2)).color_override(Color::from_rgba(40, 40, 40, 255));
+                    vec![
+                        panel.clone().size((18, 2, 34)),
+                        strap.clone().offset((3, 0, 20)),
+                        strap.clone().offset((3, 0, -20)),
+                        strap.clone().offset((-3, 0, 20)),
+                        strap.offset((-3, 0, -20)),
+                        panel.clone().size((2, 1, 2)).offset((3, 12, 0)),
+                        panel.size((2, 1, 2)).offset((-3, 12, 0)),
+                    ]
+                }
+                DoorStyle::BathroomStall => vec![
+                    panel.clone().size((18, 1, 28)).offset((0, 0, 6)),
+                    panel.clone().size((2, 1, 2)).offset((2, 14, 6)),
+                    panel.size((2, 1, 2)).offset((-2, 14, 6)),
+                ],
+            };
+
+            let mut mapping = doors::swing(parts, door.state);
+            mapping.extend(match door.style {
+                // Stalls are open at the bottom
+                DoorStyle::BathroomStall => DOOR_FRAME[1..].to_vec(),
+                _ => DOOR_FRAME.clone(),
+            });
+            Some(mapping)
+        },
+
+        //==================================================================================
+        // Road pieces, laid out in roads::ROAD_PIECES
+        //==================================================================================
+        &roads::road_pattern() => |captures, _| {
+            roads::road_mapping(captures.get(0).unwrap().as_str())
+        },
+
+        //==================================================================================
+        // Water and zones, which become volumes in the report instead of bricks
+        //==================================================================================
+        r"^(?:\d+x\d+(?:x\d+|[Ff])? )?(?:Water|Zone|Physical Zone)(?: Brick| Plate)?(?: \d+x\d+(?:x\d+|[Ff])?)?$" => |_, from| {
+            volumes::volume_size(&from.base.ui_name)?;
+            Some(vec![])
+        },
+
+        //==================================================================================
+        // Octos of any size, crossed for the plus bricks
+        //==================================================================================
+        r"^(\d+)x(\d+)(?:x(\d+))? Octo(?: (Plate|Brick90|Plus Vert|Plus Horz|Plus Plus))?$" => |captures, _| {
+            let width: u32 = captures.get(1).unwrap().as_str().parse().ok()?;
+            let length: u32 = captures.get(2).unwrap().as_str().parse().ok()?;
+            let height: Option<u32> = match captures.get(3) {
+                Some(g) => Some(g.as_str().parse().ok()?),
+                None => None,
+            };
+            let kind = captures.get(4).map(|g| g.as_str());
+
+            // Brick90 octos lie along their width, with the length across
+            if kind == Some("Brick90") {
+                if height.is_some() {
+                    return None;
+                }
+                return Some(vec![Octo::new(length, width * 10, OctoAxis::X).desc()]);
+            }
+
+            if width != length || width == 0 {
+                return None;
+            }
+            let tall = if kind == Some("Plate") {
+                if height.is_some() {
+                    return None;
+                }
+                4
+            } else {
+                height.unwrap_or(1) * 12
+            };
+            let across = width * 10;
+
+            let octos = match kind {
+                Some("Plus Vert") => vec![
+                    Octo::new(width, tall, OctoAxis::Vertical),
+                    Octo::new(width, across, OctoAxis::X),
+                ],
+                Some("Plus Horz") => vec![
+                    Octo::new(width, across, OctoAxis::X),
+                    Octo::new(width, across, OctoAxis::Y),
+                ],
+                Some("Plus Plus") => vec![
+                    Octo::new(width, tall, OctoAxis::Vertical),
+                    Octo::new(width, across, OctoAxis::X),
+                    Octo::new(width, across, OctoAxis::Y),
+                ],
+                _ => vec![Octo::new(width, tall, OctoAxis::Vertical)],
+            };
+            Some(octos.into_iter().map(Octo::desc).collect())
+        },
+
+        //==================================================================================
+        // Octo T junctions, running through the brick with a branch out of one side
+        //==================================================================================
+        r"^2x2x2 Octo T(?: (Vert|Horz|inv))?$" => |captures, _| {
+            let asset = BrickDesc::new("B_2x_Octo_T");
+            Some(match captures.get(1).map(|g| g.as_str()) {
+                Some("Vert") => octo_t(
+                    asset,
+                    Octo::new(2, 24, OctoAxis::Vertical),
+                    Octo::new(2, 10, OctoAxis::Y),
+                    (0, -5, 0),
+                ),
+                Some("Horz") => octo_t(
+                    asset.direction_override(YNegative),
+                    Octo::new(2, 20, OctoAxis::Y),
+                    Octo::new(2, 10, OctoAxis::X),
+                    (5, 0, 0),
+                ),
+                Some(_) => octo_t(
+                    asset.direction_override(YNegative).rotation_offset(0),
+                    Octo::new(2, 20, OctoAxis::Y),
+                    Octo::new(2, 12, OctoAxis::Vertical),
+                    (0, 0, -6),
+                ),
+                None => octo_t(
+                    asset.direction_override(YNegative).rotation_offset(2),
+                    Octo::new(2, 20, OctoAxis::Y),
+                    Octo::new(2, 12, OctoAxis::Vertical),
+                    (0, 0, 6),
+                ),
+            })
+        },
+
+        //==================================================================================
+        // Grill and lattice plates of any size, tiled from the 8x8 lattice plate
+        //==================================================================================
+        r"^(\d+)x(\d+)[Ff]? (?:Grill|Lattice)(?: Plate)?(?: (?P<corner>Corner)| (?P<angled>Angled|45°?))?$" => |captures, _| {
+            let width: u32 = captures.get(1).unwrap().as_str().parse().ok()?;
+            let length: u32 = captures.get(2).unwrap().as_str().parse().ok()?;
+            if width == 0 || length == 0 {
+                return None;
+            }
+            let shape = if captures.name("corner").is_some() {
+                LatticeShape::Corner
+            } else if captures.name("angled").is_some() {
+                LatticeShape::Angled
+            } else {
+                LatticeShape::Plate
+            };
+            Some(LatticePlate::new(width, length, shape).mapping())
+        },
+
+        //==================================================================================
+        // Fractional sizes, like Brick_SmallBricks by Kris
+        // (Based off of Vertical Print Plate Pack by Emil)
+        //==================================================================================
+        r"^([\d.x]*\.[\d.x]*?)([Ff])?( Centered| Edge)?( Corner)?( No Overlap)?$" => |captures, _| {
+            // Like `0.5x1` or `0.25x0.25x2`, with a fraction somewhere
+            let sizes: Vec<f32> = captures
+                .get(1)
+                .unwrap()
+                .as_str()
+                .split('x')
+                .map(|d| d.parse().ok())
+                .collect::<Option<_>>()?;
+            let (width, length, height) = match sizes[..] {
+                [width, length] => (width, length, None),
+                [width, length, height] if captures.get(2).is_none() => (width, length, Some(height)),
+                _ => return None,
+            };
+
+            // Sizes round to the nearest unit, halves rounding down
+            let round = |d: f32| (d - 0.5).ceil().max(0.0) as u32;
+            let z = if captures.get(2).is_some() {
+                2
+            } else {
+                round(height.unwrap_or(1.0) * 6.0)
+            };
+            let x = round(width * 5.0);
+            let y = round(length * 5.0);
+            if x == 0 || y == 0 || z == 0 {
+                return None;
+            }
+
+            let non_priority = captures.get(5).is_some();
+
+            if captures.get(4).is_some() {
+                // An L along two edges of a stud
+                if x != y || x >= 5 {
+                    return None;
+                }
+                let t = x as i32;
+                let rest = 5 - x;
+                return Some(vec![
+                    BrickDesc::new("PB_DefaultMicroBrick").size((x, x, z)).offset((5 - t, t - 5, 0)),
+                    BrickDesc::new("PB_DefaultMicroBrick").size((x, rest, z)).offset((-t, t - 5, 0)),
+                    BrickDesc::new("PB_DefaultMicroBrick").size((rest, x, z)).offset((5 - t, t, 0)),
+                ].into_iter().map(|b| b.non_priority(non_priority)).collect());
+            }
+
+            // Bricks sit against the edge of the studs they take up unless centered
+            let offset = if captures.get(3).is_some_and(|g| g.as_str() == " Centered") {
+                (0, 0, 0)
+            } else {
+                (
+                    width.ceil() as i32 * 5 - x as i32,
+                    length.ceil() as i32 * 5 - y as i32,
+                    0,
+                )
+            };
+
+            Some(vec![BrickDesc::new("PB_DefaultMicroBrick")
+                .size((y, x, z))
+                .offset(offset)
+                .non_priority(non_priority)])
+        },
         // TODO: Remove (?: Print)? when prints exist
         //==================================================================================
         // Ramp Support for Addons:
@@ -1605,8 +1651,8 @@ lazy_static! {
         //==================================================================================
         r"(?P<angle>25|45)° Crest (?:(?P<end>End)|(?P<corner>Corner)|(?P<length>\d+)x)" => |captures, _| {
             let (z, offset) = match captures.name("angle").unwrap().as_str() {
-                s if s == "25" => (4, -2),
-                s if s == "45" => (6, 0),
+                "25" => (4, -2),
+                "45" => (6, 0),
                 _ => return None,
             };
 
@@ -1640,7 +1686,7 @@ lazy_static! {
         r"^(\d+)x(\d+) Base$" => |captures, _| {
             let width: u32 = captures.get(1).unwrap().as_str().parse().ok()?;
             let length: u32 = captures.get(2).unwrap().as_str().parse().ok()?;
-            Some(vec![BrickDesc::new("PB_DefaultBrick").size((width * 5, length * 5, 2))])
+            Some(vec![BrickDesc::new("PB_DefaultBrick").size((width * 5, length * 5, 2)).baseplate()])
         },
 
         //==================================================================================
@@ -1649,7 +1695,7 @@ lazy_static! {
         //==================================================================================
         r"^(\d+)x Cube(?: (\d+)H)?$" => |captures, _| {
             let size: u32 = captures.get(1).unwrap().as_str().parse().ok()?;
-            let extension: Option<u32> = captures.get(2).map(|m| m.as_str().parse().ok()).flatten();
+            let extension: Option<u32> = captures.get(2).and_then(|m| m.as_str().parse().ok());
             let height = if let Some(n) = extension {
                 size * n * 5
             } else {
@@ -1892,5 +1938,134 @@ lazy_static! {
                 BrickDesc::new("PB_DefaultMicroBrick").size((3, 1, 2)).offset((4, 0, -2)),
             ])
         },
+
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        provider::{BuiltinMappings, MappingProvider},
+        test_util::brick,
+    };
+
+    type Part = ((u32, u32, u32), (i32, i32, i32));
+
+    /// The fractional bricks that used to be listed by name, with their parts
+    /// and whether they give way to overlapping bricks.
+    const FRACTIONAL: &[(&str, bool, &[Part])] = &[
+        ("0.75x0.75F", false, &[((4, 4, 2), (1, 1, 0))]),
+        ("0.75x0.75F Centered", false, &[((4, 4, 2), (0, 0, 0))]),
+        ("0.75x1F", false, &[((5, 4, 2), (1, 0, 0))]),
+        ("0.75x1F Centered", false, &[((5, 4, 2), (0, 0, 0))]),
+        ("0.75x2F", false, &[((10, 4, 2), (1, 0, 0))]),
+        ("0.75x2F No Overlap", true, &[((10, 4, 2), (1, 0, 0))]),
+        ("0.75x1", false, &[((5, 4, 6), (1, 0, 0))]),
+        ("0.75x2", false, &[((10, 4, 6), (1, 0, 0))]),
+        ("0.5x0.5 Edge", false, &[((2, 2, 6), (3, 3, 0))]),
+        ("0.5x0.5 Edge No Overlap", true, &[((2, 2, 6), (3, 3, 0))]),
+        ("0.5x0.5F", false, &[((2, 2, 2), (3, 3, 0))]),
+        ("0.5x0.5F Centered", false, &[((2, 2, 2), (0, 0, 0))]),
+        ("0.5x0.5F No Overlap", true, &[((2, 2, 2), (3, 3, 0))]),
+        ("0.5x1F", false, &[((5, 2, 2), (3, 0, 0))]),
+        ("0.50x1F Centered", false, &[((5, 2, 2), (0, 0, 0))]),
+        ("0.5x1F No Overlap", true, &[((5, 2, 2), (3, 0, 0))]),
+        ("0.5x2F", false, &[((10, 2, 2), (3, 0, 0))]),
+        ("0.5x2F No Overlap", true, &[((10, 2, 2), (3, 0, 0))]),
+        ("0.5x1", false, &[((5, 2, 6), (3, 0, 0))]),
+        ("0.5x1 No Overlap", true, &[((5, 2, 6), (3, 0, 0))]),
+        ("0.5x2", false, &[((10, 2, 6), (3, 0, 0))]),
+        ("0.5x2 No Overlap", true, &[((10, 2, 6), (3, 0, 0))]),
+        ("0.25x0.25 Edge", false, &[((1, 1, 6), (4, 4, 0))]),
+        ("0.25x0.25 Edge No Overlap", true, &[((1, 1, 6), (4, 4, 0))]),
+        ("0.25x0.25F", false, &[((1, 1, 2), (4, 4, 0))]),
+        ("0.25x0.25F Centered", false, &[((1, 1, 2), (0, 0, 0))]),
+        ("0.25x0.25F No Overlap", true, &[((1, 1, 2), (4, 4, 0))]),
+        ("0.25x1F", false, &[((5, 1, 2), (4, 0, 0))]),
+        ("0.25x1F Centered", false, &[((5, 1, 2), (0, 0, 0))]),
+        ("0.25x1F No Overlap", true, &[((5, 1, 2), (4, 0, 0))]),
+        ("0.25x1", false, &[((5, 1, 6), (4, 0, 0))]),
+        ("0.25x2F", false, &[((10, 1, 2), (4, 0, 0))]),
+        ("0.25x2F No Overlap", true, &[((10, 1, 2), (4, 0, 0))]),
+        ("0.25x2", false, &[((10, 1, 6), (4, 0, 0))]),
+        (
+            "0.25x0.25F Corner",
+            false,
+            &[
+                ((1, 1, 2), (4, -4, 0)),
+                ((1, 4, 2), (-1, -4, 0)),
+                ((4, 1, 2), (4, 1, 0)),
+            ],
+        ),
+        (
+            "0.25x0.25 Corner",
+            false,
+            &[
+                ((1, 1, 6), (4, -4, 0)),
+                ((1, 4, 6), (-1, -4, 0)),
+                ((4, 1, 6), (4, 1, 0)),
+            ],
+        ),
+        (
+            "0.5x0.5F Corner",
+            false,
+            &[
+                ((2, 2, 2), (3, -3, 0)),
+                ((2, 3, 2), (-2, -3, 0)),
+                ((3, 2, 2), (3, 2, 0)),
+            ],
+        ),
+        (
+            "0.5x0.5 Corner",
+            false,
+            &[
+                ((2, 2, 6), (3, -3, 0)),
+                ((2, 3, 6), (-2, -3, 0)),
+                ((3, 2, 6), (3, 2, 0)),
+            ],
+        ),
+        (
+            "0.75x0.75F Corner",
+            false,
+            &[
+                ((4, 4, 2), (1, -1, 0)),
+                ((4, 1, 2), (-4, -1, 0)),
+                ((1, 4, 2), (1, 4, 0)),
+            ],
+        ),
+        (
+            "0.75x0.75 Corner",
+            false,
+            &[
+                ((4, 4, 6), (1, -1, 0)),
+                ((4, 1, 6), (-4, -1, 0)),
+                ((1, 4, 6), (1, 4, 0)),
+            ],
+        ),
     ];
+
+    #[test]
+    fn fractional_sizes_match_the_old_list() {
+        let brick = brick(&["1x1\" 0 0 0.3 0 0 3  0 0 1 1 1"]);
+        for &(ui_name, non_priority, parts) in FRACTIONAL {
+            let mapping = BuiltinMappings.map_brick(ui_name, &brick).expect(ui_name);
+            let mapped: Vec<Part> = mapping.iter().map(|desc| (desc.size, desc.offset)).collect();
+            assert_eq!(mapped, parts, "{}", ui_name);
+            for desc in &mapping {
+                assert_eq!(desc.asset, "PB_DefaultMicroBrick", "{}", ui_name);
+                assert_eq!(desc.non_priority, non_priority, "{}", ui_name);
+            }
+        }
+    }
+
+    #[test]
+    fn whole_sizes_are_not_fractional() {
+        // The first pattern to match a ui_name ends the lookup, so whole sizes
+        // must not match the fractional pattern
+        for ui_name in ["1x2 Centered", "4x4 Corner", "2x2F No Overlap", "1x1x2 Edge"] {
+            let patterns = BRICK_MAP_REGEX.iter().filter(|(regex, _)| regex.is_match(ui_name));
+            assert_eq!(patterns.count(), 0, "{}", ui_name);
+        }
+    }
 }
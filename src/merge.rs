@@ -0,0 +1,195 @@
+use crate::{bounds::Bounds, new_write_data, table::Table, ConvertReport};
+use brs::chrono::Utc;
+use std::collections::{HashMap, HashSet};
+
+/// Side length of the cells used to find overlapping bricks, in Brickadia units.
+const OVERLAP_CELL_SIZE: i32 = 160;
+
+/// A converted save to place into a merged save.
+pub struct MergeInput {
+    pub report: ConvertReport,
+    /// Added to the position of every brick, in Brickadia units.
+    pub offset: (i32, i32, i32),
+}
+
+/// A brick that shares volume with a brick from an earlier input.
+/// Brick indices refer to the merged `write_data.bricks`.
+#[derive(Debug, Clone, Copy)]
+pub struct Overlap {
+    pub input: usize,
+    pub brick: usize,
+    pub other_input: usize,
+    pub other_brick: usize,
+}
+
+pub struct MergeReport {
+    pub write_data: brs::WriteData,
+    pub unknown_ui_names: HashMap<String, usize>,
+    pub count_success: usize,
+    pub count_failure: usize,
+    pub overlaps: Vec<Overlap>,
+}
+
+/// Merges converted saves into one, unifying their color, asset, material
+/// and owner tables. Bricks keep the order of their inputs.
+pub fn merge(inputs: impl IntoIterator<Item = MergeInput>) -> MergeReport {
+    let mut merged = MergeReport {
+        write_data: new_write_data(String::new(), vec![], Utc::now()),
+        unknown_ui_names: HashMap::new(),
+        count_success: 0,
+        count_failure: 0,
+        overlaps: vec![],
+    };
+
+    let data = &mut merged.write_data;
+    let mut colors = Table::new(&mut data.colors, |c| -> u32 { (*c).into() });
+    let mut assets = Table::new(&mut data.brick_assets, String::clone);
+    let mut materials = Table::new(&mut data.materials, String::clone);
+    let mut owners = Table::new(&mut data.brick_owners, |u| (u.id, u.name.clone()));
+    let mut descriptions = vec![];
+    let mut brick_inputs = vec![];
+
+    for (input_index, MergeInput { report, offset }) in inputs.into_iter().enumerate() {
+        let from = report.write_data;
+
+        let color_map: Vec<_> = from.colors.into_iter().map(|c| colors.index(c)).collect();
+        let asset_map: Vec<_> = from.brick_assets.into_iter().map(|a| assets.index(a)).collect();
+        let material_map: Vec<_> = from.materials.into_iter().map(|m| materials.index(m)).collect();
+        let owner_map: Vec<_> = from.brick_owners.into_iter().map(|o| owners.index(o)).collect();
+
+        for mut brick in from.bricks {
+            brick.asset_name_index = asset_map[brick.asset_name_index as usize];
+            brick.material_index = material_map[brick.material_index as usize];
+            brick.owner_index = owner_map[brick.owner_index as usize];
+            if let brs::ColorMode::Set(index) = brick.color {
+                brick.color = brs::ColorMode::Set(color_map[index as usize]);
+            }
+            brick.position = (
+                brick.position.0 + offset.0,
+                brick.position.1 + offset.1,
+                brick.position.2 + offset.2,
+            );
+            data.bricks.push(brick);
+            brick_inputs.push(input_index);
+        }
+
+        if !from.description.is_empty() {
+            descriptions.push(from.description);
+        }
+
+        for (ui_name, count) in report.unknown_ui_names {
+            *merged.unknown_ui_names.entry(ui_name).or_default() += count;
+        }
+        merged.count_success += report.count_success;
+        merged.count_failure += report.count_failure;
+    }
+
+    data.description = descriptions.join("\n");
+    merged.overlaps = find_overlaps(data, &brick_inputs);
+    merged
+}
+
+/// Finds bricks sharing volume with bricks from other inputs.
+fn find_overlaps(data: &brs::WriteData, brick_inputs: &[usize]) -> Vec<Overlap> {
+    let mut cells: HashMap<(i32, i32, i32), Vec<usize>> = HashMap::new();
+    let mut overlaps = vec![];
+    let bounds: Vec<_> = data
+        .bricks
+        .iter()
+        .map(|b| Bounds::of_brick(&data.brick_assets[b.asset_name_index as usize], b))
+        .collect();
+
+    for (index, brick_bounds) in bounds.iter().enumerate() {
+        let input = brick_inputs[index];
+        let mut seen = HashSet::new();
+        let min = cell_of(brick_bounds.min);
+        let max = cell_of(brick_bounds.max);
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    let cell = cells.entry((x, y, z)).or_default();
+
+                    for &other in cell.iter() {
+                        let other_input = brick_inputs[other];
+                        if other_input != input
+                            && seen.insert(other)
+                            && brick_bounds.overlaps(&bounds[other])
+                        {
+                            overlaps.push(Overlap {
+                                input,
+                                brick: index,
+                                other_input,
+                                other_brick: other,
+                            });
+                        }
+                    }
+
+                    cell.push(index);
+                }
+            }
+        }
+    }
+
+    overlaps
+}
+
+fn cell_of((x, y, z): (i32, i32, i32)) -> (i32, i32, i32) {
+    (
+        x.div_euclid(OVERLAP_CELL_SIZE),
+        y.div_euclid(OVERLAP_CELL_SIZE),
+        z.div_euclid(OVERLAP_CELL_SIZE),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    fn input(lines: &[&str], offset: (i32, i32, i32)) -> MergeInput {
+        MergeInput {
+            report: convert(lines, &ConvertOptions::default()),
+            offset,
+        }
+    }
+
+    #[test]
+    fn offsets_bricks_and_unifies_tables() {
+        let merged = merge(vec![
+            input(&[r#"2x2" 0 0 0.3 0 0 3  0 0 1 1 1"#], (0, 0, 0)),
+            input(&[r#"2x2" 0 0 0.3 0 0 5  0 0 1 1 1"#], (100, 0, 0)),
+        ]);
+
+        let bricks = &merged.write_data.bricks;
+        assert_eq!(bricks.len(), 2);
+        assert_eq!(bricks[1].position.0, bricks[0].position.0 + 100);
+        assert_eq!(bricks[0].asset_name_index, bricks[1].asset_name_index);
+        assert_eq!(merged.write_data.brick_assets.len(), 1);
+        assert_eq!(bricks[0].color, brs::ColorMode::Set(3));
+        assert_eq!(bricks[1].color, brs::ColorMode::Set(5));
+        assert_eq!(merged.count_success, 2);
+        assert!(merged.overlaps.is_empty());
+    }
+
+    #[test]
+    fn finds_overlaps_between_inputs_only() {
+        let merged = merge(vec![
+            input(
+                &[
+                    r#"2x2" 0 0 0.3 0 0 3  0 0 1 1 1"#,
+                    r#"2x2" 0 0 0.3 0 0 3  0 0 1 1 1"#,
+                ],
+                (0, 0, 0),
+            ),
+            input(&[r#"2x2" 0.5 0 0.3 0 0 3  0 0 1 1 1"#], (0, 0, 0)),
+        ]);
+
+        let pairs: Vec<_> = merged
+            .overlaps
+            .iter()
+            .map(|o| (o.brick, o.other_brick))
+            .collect();
+        assert_eq!(pairs, vec![(2, 0), (2, 1)]);
+    }
+}
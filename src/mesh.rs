@@ -0,0 +1,335 @@
+use crate::bounds::asset_half_extents;
+use brs::Direction::*;
+
+/// Height of the lip at the low end of ramps, in Brickadia units.
+const RAMP_LIP: f32 = 2.0;
+/// Width of the flat top of ramps, in Brickadia units.
+const RAMP_TOP: f32 = 10.0;
+
+/// Polygon geometry of a brick in world space, in Brickadia units.
+/// Faces index into `vertices` and are wound counter-clockwise seen from outside.
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl Mesh {
+    /// Splits every face into triangles, fanning out from its first vertex.
+    pub fn triangles(&self) -> impl Iterator<Item = [[f32; 3]; 3]> + '_ {
+        self.faces.iter().flat_map(move |face| {
+            (1..face.len().saturating_sub(1)).map(move |i| {
+                [
+                    self.vertices[face[0]],
+                    self.vertices[face[i]],
+                    self.vertices[face[i + 1]],
+                ]
+            })
+        })
+    }
+}
+
+/// Builds the geometry of a brick whose asset is `asset`.
+///
+/// Wedges, ramps, crests and corners get their own shapes. Everything else,
+/// including fixed size `B_*` assets, is represented by its bounding box.
+///
+/// Micro wedges and side wedges are triangles seen from above, standing as
+/// tall as the brick. Micro wedges only slope when turned onto their side.
+pub fn brick_mesh(asset: &str, brick: &brs::Brick) -> Mesh {
+    let (x, y, z) = asset_half_extents(asset, brick.size);
+    let (x, y, z) = (x as f32, y as f32, z as f32);
+
+    let mut mesh = match asset {
+        "PB_DefaultWedge" => corner_prism((x, y, z), [false, true, true, false], 0.0),
+        "PB_DefaultMicroWedge" => upright_triangle((x, y, z), 2),
+        "PB_DefaultSideWedge" | "PB_DefaultSideWedgeTile" => upright_triangle((x, y, z), 0),
+        "PB_DefaultMicroWedgeCorner"
+        | "PB_DefaultMicroWedgeOuterCorner"
+        | "PB_DefaultMicroWedgeTriangleCorner" => {
+            corner_prism((x, y, z), [false, true, false, false], 0.0)
+        }
+        "PB_DefaultMicroWedgeInnerCorner" => {
+            corner_prism((x, y, z), [false, true, true, true], 0.0)
+        }
+        "PB_DefaultRamp" => ramp((x, y, z)),
+        "PB_DefaultRampInverted" => invert(ramp((x, y, z))),
+        "PB_DefaultRampCorner" => corner_prism((x, y, z), [false, true, false, false], RAMP_LIP),
+        "PB_DefaultRampCornerInverted" => invert(corner_prism(
+            (x, y, z),
+            [false, true, false, false],
+            RAMP_LIP,
+        )),
+        "PB_DefaultRampInnerCorner" => corner_prism((x, y, z), [false, true, true, true], RAMP_LIP),
+        "PB_DefaultRampInnerCornerInverted" => {
+            invert(corner_prism((x, y, z), [false, true, true, true], RAMP_LIP))
+        }
+        "PB_DefaultRampCrest" | "PB_DefaultRampCrestEnd" | "PB_DefaultRampCrestCorner" => {
+            crest((x, y, z))
+        }
+        _ => corner_prism((x, y, z), [true; 4], 0.0),
+    };
+
+    for vertex in &mut mesh.vertices {
+        *vertex = orient(*vertex, brick);
+    }
+
+    mesh
+}
+
+/// A box whose top corners are either at full height or `lip` above the bottom.
+/// Corners are ordered `(-x, -y)`, `(x, -y)`, `(x, y)`, `(-x, y)`.
+fn corner_prism((x, y, z): (f32, f32, f32), high: [bool; 4], lip: f32) -> Mesh {
+    let corners = [(-x, -y), (x, -y), (x, y), (-x, y)];
+    let lip = lip.min(2.0 * z);
+
+    let mut vertices: Vec<_> = corners.iter().map(|&(cx, cy)| [cx, cy, -z]).collect();
+    for (&(cx, cy), &high) in corners.iter().zip(&high) {
+        vertices.push([cx, cy, if high { z } else { lip - z }]);
+    }
+
+    let mut faces = vec![vec![3, 2, 1, 0]];
+    // Split the top along the diagonal that keeps the highest corners together.
+    if high[0] == high[2] {
+        faces.push(vec![4, 5, 6]);
+        faces.push(vec![4, 6, 7]);
+    } else {
+        faces.push(vec![4, 5, 7]);
+        faces.push(vec![5, 6, 7]);
+    }
+    for i in 0..4 {
+        let j = (i + 1) % 4;
+        faces.push(vec![i, j, j + 4, i + 4]);
+    }
+
+    Mesh { vertices, faces }
+}
+
+/// Extrudes a polygon in the XY plane along Z.
+/// The polygon is wound counter-clockwise seen from above.
+fn prism(polygon: &[(f32, f32)], z: f32) -> Mesh {
+    let n = polygon.len();
+    let mut vertices: Vec<_> = polygon.iter().map(|&(px, py)| [px, py, -z]).collect();
+    vertices.extend(polygon.iter().map(|&(px, py)| [px, py, z]));
+
+    let mut faces = vec![(0..n).rev().collect::<Vec<_>>(), (n..2 * n).collect()];
+    for i in 0..n {
+        let j = (i + 1) % n;
+        faces.push(vec![i, j, j + n, i + n]);
+    }
+
+    Mesh { vertices, faces }
+}
+
+/// A box cut in half diagonally seen from above, without the corner at
+/// `missing`. Corners are ordered like in [`corner_prism`].
+fn upright_triangle((x, y, z): (f32, f32, f32), missing: usize) -> Mesh {
+    let corners = [(-x, -y), (x, -y), (x, y), (-x, y)];
+    let triangle: Vec<_> = (1..4).map(|i| corners[(missing + i) % 4]).collect();
+    prism(&triangle, z)
+}
+
+/// Extrudes a profile in the XZ plane along Y.
+/// The profile is wound counter-clockwise seen from -Y.
+fn extrude(profile: &[(f32, f32)], y: f32) -> Mesh {
+    let n = profile.len();
+    let mut vertices: Vec<_> = profile.iter().map(|&(px, pz)| [px, -y, pz]).collect();
+    vertices.extend(profile.iter().map(|&(px, pz)| [px, y, pz]));
+
+    let mut faces = vec![(0..n).collect::<Vec<_>>(), (n..2 * n).rev().collect()];
+    for i in 0..n {
+        let j = (i + 1) % n;
+        faces.push(vec![i, i + n, j + n, j]);
+    }
+
+    Mesh { vertices, faces }
+}
+
+/// A slope rising towards +X with a lip at the bottom and a flat top.
+fn ramp((x, y, z): (f32, f32, f32)) -> Mesh {
+    let lip = RAMP_LIP.min(2.0 * z);
+    let top = RAMP_TOP.min(2.0 * x);
+    extrude(&[(-x, -z), (x, -z), (x, z), (x - top, z), (-x, lip - z)], y)
+}
+
+/// Two slopes meeting at a ridge along Y.
+fn crest((x, y, z): (f32, f32, f32)) -> Mesh {
+    let lip = RAMP_LIP.min(2.0 * z);
+    extrude(
+        &[(-x, -z), (x, -z), (x, lip - z), (0.0, z), (-x, lip - z)],
+        y,
+    )
+}
+
+/// Flips a shape upside down.
+fn invert(mut mesh: Mesh) -> Mesh {
+    for vertex in &mut mesh.vertices {
+        vertex[2] = -vertex[2];
+    }
+    for face in &mut mesh.faces {
+        face.reverse();
+    }
+    mesh
+}
+
+/// Moves a point from brick space into world space.
+/// Matches the axis mapping of [`world_half_extents`](crate::bounds::world_half_extents).
+fn orient([mut x, mut y, z]: [f32; 3], brick: &brs::Brick) -> [f32; 3] {
+    for _ in 0..brick.rotation as u8 {
+        let (rx, ry) = (-y, x);
+        x = rx;
+        y = ry;
+    }
+
+    let (wx, wy, wz) = match brick.direction {
+        XPositive => (z, -y, x),
+        XNegative => (-z, y, x),
+        YPositive => (y, z, x),
+        YNegative => (-y, -z, x),
+        ZPositive => (x, y, z),
+        ZNegative => (x, -y, -z),
+    };
+
+    let (px, py, pz) = brick.position;
+    [wx + px as f32, wy + py as f32, wz + pz as f32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brick(
+        size: (u32, u32, u32),
+        direction: brs::Direction,
+        rotation: brs::Rotation,
+    ) -> brs::Brick {
+        brs::Brick {
+            asset_name_index: 0,
+            size,
+            position: (0, 0, 0),
+            direction,
+            rotation,
+            collision: true,
+            visibility: true,
+            material_index: 0,
+            color: brs::ColorMode::Set(0),
+            owner_index: 0,
+        }
+    }
+
+    fn has_vertex(mesh: &Mesh, at: [f32; 3]) -> bool {
+        mesh.vertices.contains(&at)
+    }
+
+    /// Positive when the faces are wound outwards.
+    fn volume(mesh: &Mesh) -> f32 {
+        mesh.triangles()
+            .map(|[a, b, c]| {
+                let cross = [
+                    b[1] * c[2] - b[2] * c[1],
+                    b[2] * c[0] - b[0] * c[2],
+                    b[0] * c[1] - b[1] * c[0],
+                ];
+                (a[0] * cross[0] + a[1] * cross[1] + a[2] * cross[2]) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn faces_are_wound_outwards() {
+        let upright = brick((10, 10, 6), ZPositive, brs::Rotation::Deg0);
+        for asset in [
+            "PB_DefaultBrick",
+            "PB_DefaultWedge",
+            "PB_DefaultMicroWedge",
+            "PB_DefaultSideWedge",
+            "PB_DefaultMicroWedgeCorner",
+            "PB_DefaultMicroWedgeInnerCorner",
+            "PB_DefaultRamp",
+            "PB_DefaultRampInverted",
+            "PB_DefaultRampCrest",
+        ] {
+            assert!(volume(&brick_mesh(asset, &upright)) > 0.0, "{}", asset);
+        }
+
+        let full = volume(&brick_mesh("PB_DefaultBrick", &upright));
+        let half = volume(&brick_mesh("PB_DefaultSideWedge", &upright));
+        assert!((full - 2.0 * half).abs() < 0.01);
+    }
+
+    #[test]
+    fn micro_wedges_are_triangles_seen_from_above() {
+        let wedge = brick_mesh(
+            "PB_DefaultMicroWedge",
+            &brick((4, 4, 2), ZPositive, brs::Rotation::Deg0),
+        );
+        assert_eq!(wedge.vertices.len(), 6);
+        assert!(!has_vertex(&wedge, [4.0, 4.0, 2.0]));
+        assert!(has_vertex(&wedge, [-4.0, -4.0, 2.0]));
+        assert!(has_vertex(&wedge, [4.0, -4.0, 2.0]));
+
+        let turned = brick_mesh(
+            "PB_DefaultMicroWedge",
+            &brick((4, 4, 2), ZPositive, brs::Rotation::Deg90),
+        );
+        assert!(!has_vertex(&turned, [-4.0, 4.0, 2.0]));
+    }
+
+    #[test]
+    fn side_wedges_cut_the_other_corner() {
+        let wedge = brick_mesh(
+            "PB_DefaultSideWedge",
+            &brick((5, 5, 6), ZPositive, brs::Rotation::Deg0),
+        );
+        assert!(!has_vertex(&wedge, [-5.0, -5.0, 6.0]));
+        assert!(has_vertex(&wedge, [5.0, 5.0, 6.0]));
+
+        let turned = brick_mesh(
+            "PB_DefaultSideWedgeTile",
+            &brick((5, 5, 2), ZPositive, brs::Rotation::Deg180),
+        );
+        assert!(!has_vertex(&turned, [5.0, 5.0, 2.0]));
+    }
+
+    #[test]
+    fn micro_wedges_on_their_side_slope() {
+        let ramp = brick_mesh(
+            "PB_DefaultMicroWedge",
+            &brick((10, 10, 10), YPositive, brs::Rotation::Deg0),
+        );
+        let tops: Vec<_> = [(-10.0, -10.0), (10.0, -10.0), (10.0, 10.0), (-10.0, 10.0)]
+            .iter()
+            .map(|&(x, y)| {
+                ramp.vertices
+                    .iter()
+                    .filter(|v| (v[0], v[1]) == (x, y))
+                    .map(|v| v[2])
+                    .fold(f32::MIN, f32::max)
+            })
+            .collect();
+        assert_eq!(tops.iter().filter(|&&z| z == 10.0).count(), 2);
+        assert_eq!(tops.iter().filter(|&&z| z == -10.0).count(), 2);
+    }
+
+    #[test]
+    fn boxes_match_their_world_extents() {
+        use crate::bounds::world_half_extents;
+
+        for direction in [XPositive, XNegative, YPositive, YNegative, ZPositive, ZNegative] {
+            for rotation in 0..4u8 {
+                let turned = brick((10, 20, 30), direction, rotation.try_into().unwrap());
+                let mesh = brick_mesh("PB_DefaultBrick", &turned);
+                let extent = |axis: usize| {
+                    mesh.vertices.iter().map(|v| v[axis]).fold(f32::MIN, f32::max) as u32
+                };
+                assert_eq!(
+                    (extent(0), extent(1), extent(2)),
+                    world_half_extents("PB_DefaultBrick", &turned),
+                    "{:?} {}",
+                    direction,
+                    rotation
+                );
+            }
+        }
+    }
+}
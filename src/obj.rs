@@ -0,0 +1,148 @@
+use crate::{mesh::brick_mesh, resolve_color};
+use std::{
+    collections::BTreeMap,
+    io::{self, prelude::*},
+};
+
+/// Writes the visible bricks of a save as Wavefront OBJ geometry,
+/// one object per brick, in Brickadia units with Z up.
+///
+/// When `mtl_file_name` is given, faces use the materials written by
+/// [`write_mtl`] for the same save.
+pub fn write_obj(
+    data: &brs::WriteData,
+    w: &mut impl Write,
+    mtl_file_name: Option<&str>,
+) -> io::Result<()> {
+    writeln!(w, "# Converted with bls2brs")?;
+    if let Some(mtl_file_name) = mtl_file_name {
+        writeln!(w, "mtllib {}", mtl_file_name)?;
+    }
+
+    let mut vertex_count = 0;
+
+    for (index, brick) in data.bricks.iter().enumerate() {
+        if !brick.visibility {
+            continue;
+        }
+
+        let asset = &data.brick_assets[brick.asset_name_index as usize];
+        let mesh = brick_mesh(asset, brick);
+
+        writeln!(w, "o brick_{}_{}", index, asset)?;
+        if mtl_file_name.is_some() {
+            writeln!(w, "usemtl {}", material_name(data, brick.color))?;
+        }
+        for [x, y, z] in &mesh.vertices {
+            writeln!(w, "v {} {} {}", x, y, z)?;
+        }
+        for face in &mesh.faces {
+            write!(w, "f")?;
+            for vertex in face {
+                write!(w, " {}", vertex_count + vertex + 1)?;
+            }
+            writeln!(w)?;
+        }
+
+        vertex_count += mesh.vertices.len();
+    }
+
+    Ok(())
+}
+
+/// Writes a Wavefront MTL material for every color used by visible bricks.
+pub fn write_mtl(data: &brs::WriteData, w: &mut impl Write) -> io::Result<()> {
+    let used: BTreeMap<_, _> = data
+        .bricks
+        .iter()
+        .filter(|b| b.visibility)
+        .map(|b| (material_name(data, b.color), resolve_color(data, b.color)))
+        .collect();
+
+    for (name, color) in used {
+        writeln!(w, "newmtl {}", name)?;
+        writeln!(
+            w,
+            "Kd {:.4} {:.4} {:.4}",
+            f32::from(color.r()) / 255.0,
+            f32::from(color.g()) / 255.0,
+            f32::from(color.b()) / 255.0,
+        )?;
+        writeln!(w, "d {:.4}", f32::from(color.a()) / 255.0)?;
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+fn material_name(data: &brs::WriteData, color: brs::ColorMode) -> String {
+    match color {
+        brs::ColorMode::Set(index) if (index as usize) < data.colors.len() => {
+            format!("palette_{}", index)
+        }
+        _ => {
+            let color = resolve_color(data, color);
+            format!(
+                "custom_{:02x}{:02x}{:02x}{:02x}",
+                color.r(),
+                color.g(),
+                color.b(),
+                color.a()
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    fn lines_starting(text: &str, prefix: &str) -> Vec<String> {
+        text.lines().filter(|line| line.starts_with(prefix)).map(String::from).collect()
+    }
+
+    #[test]
+    fn writes_visible_bricks_as_objects() {
+        let report = convert(
+            &[
+                "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "2x4\" 0 4 0.3 0 0 3  0 0 1 1 0",
+                "1x1\" 4 0 0.3 0 0 5  0 0 1 1 1",
+            ],
+            &ConvertOptions::default(),
+        );
+        let mut obj = vec![];
+        write_obj(&report.write_data, &mut obj, Some("save.mtl")).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+
+        assert_eq!(lines_starting(&obj, "mtllib"), ["mtllib save.mtl"]);
+        assert_eq!(lines_starting(&obj, "o ").len(), 2);
+        assert_eq!(lines_starting(&obj, "usemtl"), ["usemtl palette_3", "usemtl palette_5"]);
+        assert_eq!(lines_starting(&obj, "v ").len(), 16);
+
+        // Faces of the second object index its own vertices
+        let faces = lines_starting(&obj, "f ");
+        assert_eq!(faces.len(), 2 * 7);
+        let last: Vec<usize> = faces[13][2..].split(' ').map(|i| i.parse().unwrap()).collect();
+        assert!(last.iter().all(|&i| (9..=16).contains(&i)));
+    }
+
+    #[test]
+    fn writes_a_material_per_color() {
+        let report = convert(
+            &[
+                "2x4\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "2x4\" 0 4 0.3 0 0 3  0 0 1 1 1",
+                "2x4\" 0 8 0.3 0 0 7  0 0 1 1 0",
+            ],
+            &ConvertOptions::default(),
+        );
+        let mut mtl = vec![];
+        write_mtl(&report.write_data, &mut mtl).unwrap();
+        let mtl = String::from_utf8(mtl).unwrap();
+
+        assert_eq!(lines_starting(&mtl, "newmtl"), ["newmtl palette_3"]);
+        assert_eq!(lines_starting(&mtl, "d "), ["d 1.0000"]);
+    }
+}
@@ -0,0 +1,318 @@
+//! Octagonal bricks of any size, built from the fixed size octo assets or
+//! approximated with microbricks and wedges.
+
+use crate::types::{BrickDesc, BrickMapping};
+
+/// Height of the flat octo assets, in Brickadia units.
+const PLATE_LENGTH: u32 = 4;
+
+/// The direction an octo runs in, in the frame of the Blockland brick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OctoAxis {
+    Vertical,
+    X,
+    Y,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OctoStyle {
+    /// The `B_*_Octo` assets where there are some for the diameter,
+    /// with microbricks filling in the rest.
+    #[default]
+    Assets,
+    /// Microbricks and wedges only.
+    Microbricks,
+}
+
+impl OctoStyle {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "assets" => Some(OctoStyle::Assets),
+            "microbricks" => Some(OctoStyle::Microbricks),
+            _ => None,
+        }
+    }
+}
+
+/// An octagonal prism centered on the brick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Octo {
+    /// Width across the flat sides, in studs.
+    pub diameter: u32,
+    /// Length along the axis, in Brickadia units.
+    pub length: u32,
+    pub axis: OctoAxis,
+}
+
+impl Octo {
+    pub fn new(diameter: u32, length: u32, axis: OctoAxis) -> Self {
+        Self {
+            diameter,
+            length,
+            axis,
+        }
+    }
+
+    /// A brick of a mapping that becomes this octo, built in the style the
+    /// conversion asks for.
+    pub fn desc(self) -> BrickDesc {
+        BrickDesc::new("PB_DefaultMicroBrick").octo(self)
+    }
+
+    /// The bricks that make up the octo.
+    pub fn mapping(&self, style: OctoStyle) -> BrickMapping {
+        if self.diameter == 0 || self.length == 0 {
+            return vec![];
+        }
+
+        match self.axis {
+            OctoAxis::Y => turn(Octo { axis: OctoAxis::X, ..*self }.mapping(style)),
+            _ => self
+                .pieces(style)
+                .into_iter()
+                .flat_map(|(piece, center)| self.piece_mapping(piece, center))
+                .collect(),
+        }
+    }
+
+    /// Splits the octo into fixed size assets along its axis, with the center
+    /// of each piece. Cubes and plates are spread evenly around whatever
+    /// microbricks need to fill in.
+    fn pieces(&self, style: OctoStyle) -> Vec<(Piece, i32)> {
+        let cube = self.diameter * 10;
+        let assets = style == OctoStyle::Assets && matches!(self.diameter, 1 | 2);
+
+        let (cubes, plates, rest) = if assets {
+            // Use as many cubes as possible without leaving a gap plates can't fill
+            (0..=self.length / cube)
+                .rev()
+                .map(|cubes| {
+                    let left = self.length - cubes * cube;
+                    (cubes, left / PLATE_LENGTH, left % PLATE_LENGTH)
+                })
+                .find(|&(_, _, rest)| rest == 0)
+                .unwrap_or_else(|| {
+                    let cubes = self.length / cube;
+                    let left = self.length - cubes * cube;
+                    (cubes, left / PLATE_LENGTH, left % PLATE_LENGTH)
+                })
+        } else {
+            (0, 0, self.length)
+        };
+
+        let mut order = vec![];
+        order.extend((0..cubes.div_ceil(2)).map(|_| Piece::Cube));
+        order.extend((0..plates.div_ceil(2)).map(|_| Piece::Plate));
+        if rest > 0 {
+            order.push(Piece::Slab(rest));
+        }
+        order.extend((0..plates / 2).map(|_| Piece::Plate));
+        order.extend((0..cubes / 2).map(|_| Piece::Cube));
+
+        let mut start = -(self.length as i32) / 2;
+        order
+            .into_iter()
+            .map(|piece| {
+                let length = match piece {
+                    Piece::Cube => cube,
+                    Piece::Plate => PLATE_LENGTH,
+                    Piece::Slab(length) => length,
+                } as i32;
+                let center = start + length / 2;
+                start += length;
+                (piece, center)
+            })
+            .collect()
+    }
+
+    fn piece_mapping(&self, piece: Piece, center: i32) -> BrickMapping {
+        let horizontal = self.axis != OctoAxis::Vertical;
+        let along = |desc: BrickDesc| {
+            if horizontal {
+                desc.offset((center, 0, 0))
+                    .rotate_by_direction()
+                    .rotation_offset(1)
+            } else {
+                desc.offset((0, 0, center))
+            }
+        };
+
+        match (piece, self.diameter) {
+            (Piece::Cube, 1) => vec![along(BrickDesc::new("B_1x_Octo"))],
+            (Piece::Cube, _) => vec![along(BrickDesc::new("B_2x_Octo"))],
+            (Piece::Plate, 1) => vec![along(BrickDesc::new("B_1x1F_Octo"))],
+            (Piece::Plate, _) => vec![along(BrickDesc::new("B_2x2F_Octo"))],
+            (Piece::Slab(length), _) if horizontal => self.horizontal_slab(length / 2, center),
+            (Piece::Slab(length), _) => self.vertical_slab(length / 2, center),
+        }
+    }
+
+    /// Half the width across the flat sides, and half the width of the cut
+    /// corners, in Brickadia units.
+    fn section(&self) -> (u32, u32) {
+        let radius = self.diameter * 5;
+        let corner = ((radius * 3 + 5) / 10).max(1);
+        (radius, corner)
+    }
+
+    fn vertical_slab(&self, height: u32, z: i32) -> BrickMapping {
+        let (radius, corner) = self.section();
+        let inner = radius - corner * 2;
+        let edge = (radius - corner) as i32;
+        // Side wedges cut the corners off, leaving out the one furthest from the center
+        let wedge = BrickDesc::new("PB_DefaultSideWedge").size((corner, corner, height));
+
+        vec![
+            BrickDesc::new("PB_DefaultMicroBrick").size((radius, inner, height)).offset((0, 0, z)).rotation_offset(0),
+            BrickDesc::new("PB_DefaultMicroBrick").size((inner, corner, height)).offset((0, edge, z)).rotation_offset(0),
+            BrickDesc::new("PB_DefaultMicroBrick").size((inner, corner, height)).offset((0, -edge, z)).rotation_offset(0),
+            wedge.clone().offset((edge, edge, z)).rotation_offset(2),
+            wedge.clone().offset((edge, -edge, z)).rotation_offset(1),
+            wedge.clone().offset((-edge, -edge, z)).rotation_offset(0),
+            wedge.offset((-edge, edge, z)).rotation_offset(3),
+        ]
+    }
+
+    fn horizontal_slab(&self, length: u32, x: i32) -> BrickMapping {
+        let (radius, corner) = self.section();
+        let inner = radius - corner * 2;
+        let edge = (radius - corner) as i32;
+        let wedge = BrickDesc::new("PB_DefaultMicroWedge").size((corner, length, corner)).microwedge_rotate(true);
+
+        vec![
+            BrickDesc::new("PB_DefaultMicroBrick").size((radius, length, inner)).offset((x, 0, 0)),
+            BrickDesc::new("PB_DefaultMicroBrick").size((inner, length, corner)).offset((x, 0, edge)),
+            BrickDesc::new("PB_DefaultMicroBrick").size((inner, length, corner)).offset((x, 0, -edge)),
+            wedge.clone().offset((x, -edge, -edge)).rotation_offset(1).inverted_wedge_rotate(true).inverted_modter_rotate(true),
+            wedge.clone().offset((x, edge, -edge)).rotation_offset(3).inverted_wedge_rotate(true).inverted_modter_rotate(true),
+            wedge.clone().offset((x, -edge, edge)).rotation_offset(3),
+            wedge.offset((x, edge, edge)).rotation_offset(1),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece {
+    Cube,
+    Plate,
+    /// Microbricks filling in a length no asset fits, in Brickadia units.
+    Slab(u32),
+}
+
+/// A T junction with an asset of its own, like the `2x2x2 Octo T` bricks.
+/// Without the octo assets, it's built from an octo running through the brick
+/// and a branch centered on `branch_offset`.
+pub fn octo_t(
+    asset: BrickDesc,
+    run: Octo,
+    branch: Octo,
+    branch_offset: (i32, i32, i32),
+) -> BrickMapping {
+    vec![
+        asset.octo_style(OctoStyle::Assets),
+        run.desc().octo_style(OctoStyle::Microbricks),
+        branch.desc().offset(branch_offset).octo_style(OctoStyle::Microbricks),
+    ]
+}
+
+/// Builds a brick of a mapping in the given style, if it's an octo, and
+/// leaves it out if it's only for another style.
+pub(crate) fn octo_descs(desc: BrickDesc, style: OctoStyle) -> BrickMapping {
+    if desc.octo_style.is_some_and(|only| only != style) {
+        return vec![];
+    }
+
+    let octo = match desc.octo {
+        Some(octo) => octo,
+        None => return vec![desc],
+    };
+
+    let (x, y, z) = desc.offset;
+    octo.mapping(style)
+        .into_iter()
+        .map(|part| {
+            let (px, py, pz) = part.offset;
+            let color_override = part.color_override.or(desc.color_override);
+            BrickDesc {
+                offset: (x + px, y + py, z + pz),
+                color_override,
+                non_priority: desc.non_priority,
+                nocollide: desc.nocollide,
+                ..part
+            }
+        })
+        .collect()
+}
+
+/// Turns a mapping a quarter turn around Z.
+fn turn(mapping: BrickMapping) -> BrickMapping {
+    mapping
+        .into_iter()
+        .map(|mut desc| {
+            let (x, y, z) = desc.offset;
+            desc.offset = (-y, x, z);
+            desc.rotation_offset = (desc.rotation_offset + 1) % 4;
+            desc
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    fn assets(mapping: &BrickMapping) -> Vec<&'static str> {
+        mapping.iter().map(|desc| desc.asset).collect()
+    }
+
+    #[test]
+    fn fills_the_length_with_cubes_and_plates() {
+        let octo = Octo::new(2, 28, OctoAxis::Vertical);
+        let pieces = octo.pieces(OctoStyle::Assets);
+        let kinds: Vec<_> = pieces.iter().map(|&(piece, _)| piece).collect();
+        assert_eq!(kinds, [Piece::Cube, Piece::Plate, Piece::Plate]);
+        let length: u32 = pieces
+            .iter()
+            .map(|(piece, _)| match piece {
+                Piece::Cube => 20,
+                Piece::Plate => PLATE_LENGTH,
+                Piece::Slab(length) => *length,
+            })
+            .sum();
+        assert_eq!(length, 28);
+
+        let slabs = octo.pieces(OctoStyle::Microbricks);
+        assert_eq!(slabs, [(Piece::Slab(28), 0)]);
+        assert_eq!(assets(&octo.mapping(OctoStyle::Microbricks)).len(), 7);
+    }
+
+    #[test]
+    fn builds_octo_descs_in_the_chosen_style() {
+        let desc = Octo::new(1, 10, OctoAxis::Vertical).desc().offset((0, 0, 5));
+        assert_eq!(assets(&octo_descs(desc.clone(), OctoStyle::Assets)), ["B_1x_Octo"]);
+
+        let microbricks = octo_descs(desc, OctoStyle::Microbricks);
+        assert!(microbricks.iter().all(|part| part.octo.is_none() && part.offset.2 == 5));
+        assert!(!assets(&microbricks).contains(&"B_1x_Octo"));
+
+        let only_assets = BrickDesc::new("B_2x_Octo_T").octo_style(OctoStyle::Assets);
+        assert!(octo_descs(only_assets, OctoStyle::Microbricks).is_empty());
+    }
+
+    #[test]
+    fn converts_octo_t_junctions_in_either_style() {
+        let lines = ["2x2x2 Octo T Vert\" 0 0 1.2 0 0 3  0 0 1 1 1"];
+        let with_assets = convert(&lines, &Default::default());
+        assert_eq!(with_assets.write_data.brick_assets, ["B_2x_Octo_T"]);
+
+        let options = ConvertOptions {
+            octo_style: OctoStyle::Microbricks,
+            ..Default::default()
+        };
+        let with_microbricks = convert(&lines, &options);
+        assert!(!with_microbricks.write_data.brick_assets.iter().any(|a| a.starts_with("B_")));
+        assert!(with_microbricks.write_data.bricks.len() > 7);
+        assert_eq!(OctoStyle::parse("microbricks"), Some(OctoStyle::Microbricks));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{bounds::Bounds, mesh::brick_mesh, resolve_color};
+use std::io::{self, prelude::*};
+
+/// Pixels between the top-down and side panels.
+const PANEL_GAP: u32 = 8;
+/// Half the side length of the markers for failed bricks, in Brickadia units.
+const MARKER_SIZE: i32 = 5;
+/// Largest side of a panel, in pixels. Larger panels take too much memory.
+pub const MAX_PREVIEW_SIZE: u32 = 8192;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PreviewOptions {
+    /// The longest side of each panel, in pixels, up to [`MAX_PREVIEW_SIZE`].
+    pub max_size: u32,
+    /// Adds a view from the side below the top-down view.
+    pub side_view: bool,
+    /// Color for the markers of bricks that failed to convert.
+    pub highlight: brs::Color,
+    pub background: brs::Color,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 1024,
+            side_view: false,
+            highlight: brs::Color::from_rgba(255, 0, 255, 255),
+            background: brs::Color::from_rgba(32, 32, 32, 255),
+        }
+    }
+}
+
+/// An RGBA image, row by row from the top.
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn write_png(&self, w: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Which way a panel looks at the save.
+#[derive(Clone, Copy)]
+enum View {
+    /// Looking down, X to the right and Y up.
+    Top,
+    /// Looking along +Y, X to the right and Z up.
+    Side,
+}
+
+impl View {
+    /// Maps a world point to panel (right, up, towards the viewer) coordinates.
+    fn project(self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        match self {
+            View::Top => [x, y, z],
+            View::Side => [x, z, -y],
+        }
+    }
+
+    fn light(self) -> [f32; 3] {
+        normalize(self.project([-0.3, -0.5, 1.0]))
+    }
+}
+
+/// Renders an orthographic preview of the visible bricks with palette colors
+/// and height shading. `failed_positions` are drawn as highlighted markers.
+pub fn render_preview(
+    data: &brs::WriteData,
+    failed_positions: &[(i32, i32, i32)],
+    options: &PreviewOptions,
+) -> Image {
+    let bounds = data
+        .bricks
+        .iter()
+        .filter(|b| b.visibility)
+        .map(|b| Bounds::of_brick(&data.brick_assets[b.asset_name_index as usize], b))
+        .chain(failed_positions.iter().map(|&p| marker_bounds(p)))
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or(Bounds {
+            min: (-MARKER_SIZE, -MARKER_SIZE, -MARKER_SIZE),
+            max: (MARKER_SIZE, MARKER_SIZE, MARKER_SIZE),
+        });
+
+    let top = render_panel(data, failed_positions, options, bounds, View::Top);
+
+    if !options.side_view {
+        return top;
+    }
+
+    let side = render_panel(data, failed_positions, options, bounds, View::Side);
+    stack(&top, &side, options.background)
+}
+
+fn render_panel(
+    data: &brs::WriteData,
+    failed_positions: &[(i32, i32, i32)],
+    options: &PreviewOptions,
+    bounds: Bounds,
+    view: View,
+) -> Image {
+    let min = view.project(to_f32(bounds.min));
+    let max = view.project(to_f32(bounds.max));
+    let (min_u, max_u) = (min[0].min(max[0]), min[0].max(max[0]));
+    let (min_v, max_v) = (min[1].min(max[1]), min[1].max(max[1]));
+    let (min_depth, max_depth) = (min[2].min(max[2]), min[2].max(max[2]));
+
+    let extent = (max_u - min_u).max(max_v - min_v).max(1.0);
+    let scale = options.max_size.clamp(1, MAX_PREVIEW_SIZE) as f32 / extent;
+    let width = (((max_u - min_u) * scale).ceil() as u32).max(1);
+    let height = (((max_v - min_v) * scale).ceil() as u32).max(1);
+
+    let mut raster = Raster {
+        width,
+        height,
+        colors: vec![options.background; (width * height) as usize],
+        depths: vec![f32::NEG_INFINITY; (width * height) as usize],
+    };
+
+    let to_pixel = |point: [f32; 3]| {
+        let [u, v, depth] = view.project(point);
+        [(u - min_u) * scale, (max_v - v) * scale, depth]
+    };
+    let light = view.light();
+
+    for brick in data.bricks.iter().filter(|b| b.visibility) {
+        let color = resolve_color(data, brick.color);
+        if color.a() == 0 {
+            continue;
+        }
+
+        let mesh = brick_mesh(&data.brick_assets[brick.asset_name_index as usize], brick);
+
+        for triangle in mesh.triangles() {
+            let normal = view.project(face_normal(triangle));
+            let lighting = 0.55 + 0.45 * dot(normal, light).max(0.0);
+            let shade = |depth: f32| {
+                let height = (depth - min_depth) / (max_depth - min_depth).max(1.0);
+                lighting * (0.6 + 0.4 * height)
+            };
+
+            raster.fill_triangle(triangle.map(to_pixel), |depth| {
+                scale_color(color, shade(depth))
+            });
+        }
+    }
+
+    for &(x, y, z) in failed_positions {
+        let marker = marker_bounds((x, y, z));
+        let a = to_pixel(to_f32(marker.min));
+        let b = to_pixel(to_f32(marker.max));
+        raster.fill_rect(
+            (a[0].min(b[0]), a[1].min(b[1])),
+            (a[0].max(b[0]), a[1].max(b[1])),
+            options.highlight,
+        );
+    }
+
+    raster.into_image()
+}
+
+struct Raster {
+    width: u32,
+    height: u32,
+    colors: Vec<brs::Color>,
+    depths: Vec<f32>,
+}
+
+impl Raster {
+    /// Fills the pixels whose centers lie in a triangle given in pixel
+    /// coordinates, keeping whatever is nearest to the viewer.
+    fn fill_triangle(&mut self, [a, b, c]: [[f32; 3]; 3], color: impl Fn(f32) -> brs::Color) {
+        let area = edge(a, b, c);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+
+        let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.0) as u32;
+        let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.0) as u32;
+        let max_x = (a[0].max(b[0]).max(c[0]).ceil() as u32).min(self.width);
+        let max_y = (a[1].max(b[1]).max(c[1]).ceil() as u32).min(self.height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let p = [x as f32 + 0.5, y as f32 + 0.5, 0.0];
+                let wa = edge(b, c, p) / area;
+                let wb = edge(c, a, p) / area;
+                let wc = edge(a, b, p) / area;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+
+                let depth = wa * a[2] + wb * b[2] + wc * c[2];
+                let index = (y * self.width + x) as usize;
+                if depth > self.depths[index] {
+                    self.depths[index] = depth;
+                    self.colors[index] = color(depth);
+                }
+            }
+        }
+    }
+
+    /// Fills a rectangle given in pixel coordinates, covering at least one pixel.
+    fn fill_rect(&mut self, min: (f32, f32), max: (f32, f32), color: brs::Color) {
+        let min_x = (min.0.floor().max(0.0) as u32).min(self.width - 1);
+        let min_y = (min.1.floor().max(0.0) as u32).min(self.height - 1);
+        let max_x = (max.0.ceil() as u32).clamp(min_x + 1, self.width);
+        let max_y = (max.1.ceil() as u32).clamp(min_y + 1, self.height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let index = (y * self.width + x) as usize;
+                self.colors[index] = color;
+                self.depths[index] = f32::INFINITY;
+            }
+        }
+    }
+
+    fn into_image(self) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .colors
+                .iter()
+                .flat_map(|c| [c.r(), c.g(), c.b(), 255])
+                .collect(),
+        }
+    }
+}
+
+/// Places `bottom` below `top`.
+fn stack(top: &Image, bottom: &Image, background: brs::Color) -> Image {
+    let width = top.width.max(bottom.width);
+    let height = top.height + PANEL_GAP + bottom.height;
+    let mut pixels: Vec<u8> = [background.r(), background.g(), background.b(), 255]
+        .iter()
+        .copied()
+        .cycle()
+        .take((width * height * 4) as usize)
+        .collect();
+
+    for (image, y_offset) in [(top, 0), (bottom, top.height + PANEL_GAP)] {
+        let row_len = (image.width * 4) as usize;
+        for (y, row) in image.pixels.chunks(row_len).enumerate() {
+            let start = ((y as u32 + y_offset) * width * 4) as usize;
+            pixels[start..start + row_len].copy_from_slice(row);
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+fn marker_bounds((x, y, z): (i32, i32, i32)) -> Bounds {
+    Bounds {
+        min: (x - MARKER_SIZE, y - MARKER_SIZE, z - MARKER_SIZE),
+        max: (x + MARKER_SIZE, y + MARKER_SIZE, z + MARKER_SIZE),
+    }
+}
+
+fn scale_color(color: brs::Color, factor: f32) -> brs::Color {
+    let scale = |c: u8| (f32::from(c) * factor).clamp(0.0, 255.0) as u8;
+    brs::Color::from_rgba(scale(color.r()), scale(color.g()), scale(color.b()), 255)
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p` in the XY plane.
+fn edge(a: [f32; 3], b: [f32; 3], p: [f32; 3]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+fn face_normal([a, b, c]: [[f32; 3]; 3]) -> [f32; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    normalize([
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ])
+}
+
+fn normalize([x, y, z]: [f32; 3]) -> [f32; 3] {
+    let length = (x * x + y * y + z * z).sqrt().max(f32::EPSILON);
+    [x / length, y / length, z / length]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn to_f32((x, y, z): (i32, i32, i32)) -> [f32; 3] {
+    [x as f32, y as f32, z as f32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::convert;
+
+    fn pixel(image: &Image, x: u32, y: u32) -> [u8; 3] {
+        let i = ((y * image.width + x) * 4) as usize;
+        [image.pixels[i], image.pixels[i + 1], image.pixels[i + 2]]
+    }
+
+    fn options(max_size: u32) -> PreviewOptions {
+        PreviewOptions {
+            max_size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fits_the_longest_side() {
+        let report = convert(&["2x4\" 0 0 0.1 0 0 3  0 0 1 1 1"], &Default::default());
+        let image = render_preview(&report.write_data, &[], &options(64));
+        assert_eq!((image.width, image.height), (64, 32));
+        assert_eq!(image.pixels.len(), 64 * 32 * 4);
+        assert_ne!(pixel(&image, 32, 16), [32, 32, 32]);
+    }
+
+    #[test]
+    fn marks_failed_bricks_away_from_the_rest() {
+        let report = convert(&["1x1\" 0 0 0.1 0 0 3  0 0 1 1 1"], &Default::default());
+        let image = render_preview(&report.write_data, &[(190, 0, 6)], &options(100));
+        assert_eq!((image.width, image.height), (100, 5));
+        assert_eq!(pixel(&image, 50, 2), [32, 32, 32]);
+        assert_eq!(pixel(&image, 98, 2), [255, 0, 255]);
+        assert_ne!(pixel(&image, 2, 2), [32, 32, 32]);
+    }
+
+    #[test]
+    fn stacks_the_side_view_below() {
+        let report = convert(&["2x2\" 0 0 0.3 0 0 3  0 0 1 1 1"], &Default::default());
+        let side_view = PreviewOptions {
+            side_view: true,
+            ..options(40)
+        };
+        let image = render_preview(&report.write_data, &[], &side_view);
+        assert_eq!((image.width, image.height), (40, 40 + PANEL_GAP + 40 * 12 / 20));
+    }
+}
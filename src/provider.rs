@@ -0,0 +1,123 @@
+//! Where brick mappings come from.
+
+use crate::{
+    mappings::{BRICK_MAP_LITERAL, BRICK_MAP_REGEX},
+    BrickMapping,
+};
+use std::{collections::HashMap, fmt};
+
+/// Decides which Brickadia bricks a Blockland brick converts to.
+pub trait MappingProvider: Send + Sync {
+    /// The bricks to convert `brick` into, or `None` if this provider has no
+    /// mapping for it.
+    fn map_brick(&self, ui_name: &str, brick: &bl_save::Brick) -> Option<BrickMapping>;
+}
+
+impl fmt::Debug for dyn MappingProvider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("MappingProvider")
+    }
+}
+
+/// The mappings that come with bls2brs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinMappings;
+
+impl MappingProvider for BuiltinMappings {
+    fn map_brick(&self, ui_name: &str, brick: &bl_save::Brick) -> Option<BrickMapping> {
+        if let Some(mapping) = BRICK_MAP_LITERAL.get(ui_name) {
+            return Some(mapping.clone());
+        }
+
+        for (regex, func) in BRICK_MAP_REGEX.iter() {
+            if let Some(captures) = regex.captures(ui_name) {
+                return func(captures, brick);
+            }
+        }
+
+        None
+    }
+}
+
+/// Fixed mappings by ui_name.
+impl MappingProvider for HashMap<String, BrickMapping> {
+    fn map_brick(&self, ui_name: &str, _brick: &bl_save::Brick) -> Option<BrickMapping> {
+        self.get(ui_name).cloned()
+    }
+}
+
+impl<F> MappingProvider for F
+where
+    F: Fn(&str, &bl_save::Brick) -> Option<BrickMapping> + Send + Sync,
+{
+    fn map_brick(&self, ui_name: &str, brick: &bl_save::Brick) -> Option<BrickMapping> {
+        self(ui_name, brick)
+    }
+}
+
+/// Asks several providers in turn and uses the first mapping found, so earlier
+/// providers take priority over later ones.
+///
+/// To add or override mappings, chain your own provider before [`BuiltinMappings`].
+#[derive(Debug, Default)]
+pub struct MappingChain {
+    providers: Vec<Box<dyn MappingProvider>>,
+}
+
+impl MappingChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a provider with a lower priority than the ones added before it.
+    pub fn with(mut self, provider: impl MappingProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl MappingProvider for MappingChain {
+    fn map_brick(&self, ui_name: &str, brick: &bl_save::Brick) -> Option<BrickMapping> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.map_brick(ui_name, brick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::brick, types::BrickDesc};
+
+    fn assets(mapping: Option<BrickMapping>) -> Vec<&'static str> {
+        mapping.unwrap_or_default().iter().map(|desc| desc.asset).collect()
+    }
+
+    #[test]
+    fn builtin_mappings_cover_literal_and_scaled_names() {
+        let brick = brick(&["2x4\" 0 0 0.3 0 0 3  0 0 1 1 1"]);
+        assert_eq!(assets(BuiltinMappings.map_brick("2x4", &brick)), ["PB_DefaultBrick"]);
+        assert_eq!(assets(BuiltinMappings.map_brick("7x9", &brick)), ["PB_DefaultBrick"]);
+        assert!(BuiltinMappings.map_brick("Weird Brick", &brick).is_none());
+    }
+
+    #[test]
+    fn earlier_providers_take_priority() {
+        let brick = brick(&["2x4\" 0 0 0.3 0 0 3  0 0 1 1 1"]);
+        let fixed: HashMap<_, _> = [(
+            String::from("2x4"),
+            vec![BrickDesc::new("PB_DefaultTile").size((10, 20, 6))],
+        )]
+        .into_iter()
+        .collect();
+        let weird = |ui_name: &str, _: &bl_save::Brick| {
+            (ui_name == "Weird Brick").then(|| vec![BrickDesc::new("B_1x1_Cone")])
+        };
+        let chain = MappingChain::new().with(fixed).with(weird).with(BuiltinMappings);
+
+        assert_eq!(assets(chain.map_brick("2x4", &brick)), ["PB_DefaultTile"]);
+        assert_eq!(assets(chain.map_brick("Weird Brick", &brick)), ["B_1x1_Cone"]);
+        assert_eq!(assets(chain.map_brick("1x1", &brick)), ["PB_DefaultBrick"]);
+        assert!(MappingChain::new().map_brick("1x1", &brick).is_none());
+    }
+}
@@ -0,0 +1,507 @@
+//! Road pieces built from a description of their lanes and which sides they
+//! connect to, painted with a configurable palette.
+
+use crate::types::{BrickDesc, BrickMapping};
+use brs::Color;
+
+/// Half the height of road pieces, in Brickadia units.
+const ROAD_HEIGHT: u32 = 2;
+/// Side length of the cells diagonal roads are built from, in Brickadia units.
+const DIAGONAL_STEP: i32 = 10;
+
+/// Widths across the road bricks of the Blockland road pack.
+pub const ROAD_PROFILE: RoadProfile = RoadProfile {
+    lane: 58,
+    stripe: 8,
+    center_line: 8,
+};
+
+/// The road pieces that are converted, by ui_name, with the widths across
+/// their roads. Add a line here to support another piece, like a diagonal
+/// road with [`RoadLayout::Diagonal`].
+pub const ROAD_PIECES: &[(&str, RoadProfile, RoadLayout)] = &[
+    (
+        "32x32 Road",
+        ROAD_PROFILE,
+        RoadLayout::Sides(160, &[RoadSide::XNegative, RoadSide::XPositive]),
+    ),
+    (
+        "32x32 Road T",
+        ROAD_PROFILE,
+        RoadLayout::Sides(
+            160,
+            &[
+                RoadSide::XNegative,
+                RoadSide::XPositive,
+                RoadSide::YPositive,
+            ],
+        ),
+    ),
+    (
+        "32x32 Road X",
+        ROAD_PROFILE,
+        RoadLayout::Sides(160, &RoadSide::ALL),
+    ),
+    (
+        "32x32 Road C",
+        ROAD_PROFILE,
+        RoadLayout::Sides(160, &[RoadSide::XNegative, RoadSide::YPositive]),
+    ),
+];
+
+/// How a road piece is laid out, with half its side length in Brickadia units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoadLayout {
+    /// Roads from the center to each of these sides, and sidewalk elsewhere.
+    /// Without any sides, the piece is all sidewalk.
+    Sides(u32, &'static [RoadSide]),
+    /// A road from corner to corner, from -X -Y to +X +Y.
+    Diagonal(u32),
+}
+
+impl RoadLayout {
+    pub fn mapping(self, profile: RoadProfile) -> BrickMapping {
+        match self {
+            RoadLayout::Sides(size, open) => RoadPiece::new(size, profile, open).mapping(),
+            RoadLayout::Diagonal(size) => diagonal_mapping(size, profile),
+        }
+    }
+}
+
+/// A pattern matching the ui_name of every piece in [`ROAD_PIECES`].
+pub(crate) fn road_pattern() -> String {
+    let names: Vec<_> = ROAD_PIECES
+        .iter()
+        .map(|(name, ..)| regex::escape(name))
+        .collect();
+    format!("^(?:{})$", names.join("|"))
+}
+
+/// Builds the road piece with this ui_name from [`ROAD_PIECES`].
+pub(crate) fn road_mapping(ui_name: &str) -> Option<BrickMapping> {
+    ROAD_PIECES
+        .iter()
+        .find(|(name, ..)| *name == ui_name)
+        .map(|(_, profile, layout)| layout.mapping(*profile))
+}
+
+/// What part of a road a brick is, so it can be colored from the [`RoadPalette`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoadPaint {
+    Asphalt,
+    /// Lines along the edges of the road and across it where roads meet.
+    Stripe,
+    CenterLine,
+}
+
+/// Colors of road bricks. Sidewalks use the color of the Blockland brick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoadPalette {
+    pub asphalt: Color,
+    pub stripe: Color,
+    pub center_line: Color,
+}
+
+impl Default for RoadPalette {
+    fn default() -> Self {
+        Self {
+            asphalt: Color::from_rgba(11, 11, 11, 255),
+            stripe: Color::from_rgba(254, 254, 232, 255),
+            center_line: Color::from_rgba(250, 200, 10, 255),
+        }
+    }
+}
+
+impl RoadPalette {
+    pub fn color(&self, paint: RoadPaint) -> Color {
+        match paint {
+            RoadPaint::Asphalt => self.asphalt,
+            RoadPaint::Stripe => self.stripe,
+            RoadPaint::CenterLine => self.center_line,
+        }
+    }
+}
+
+/// Widths across a road, in Brickadia units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoadProfile {
+    /// One lane, between the center line and a stripe.
+    pub lane: u32,
+    pub stripe: u32,
+    pub center_line: u32,
+}
+
+impl RoadProfile {
+    /// Half the width of the road from stripe to stripe.
+    fn half_width(&self) -> i32 {
+        (self.center_line / 2 + self.lane + self.stripe) as i32
+    }
+}
+
+/// A side of a road piece, in the frame of the Blockland brick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoadSide {
+    XNegative,
+    XPositive,
+    YNegative,
+    YPositive,
+}
+
+impl RoadSide {
+    pub const ALL: [RoadSide; 4] = [
+        RoadSide::XNegative,
+        RoadSide::XPositive,
+        RoadSide::YNegative,
+        RoadSide::YPositive,
+    ];
+
+    fn opposite(self) -> Self {
+        match self {
+            RoadSide::XNegative => RoadSide::XPositive,
+            RoadSide::XPositive => RoadSide::XNegative,
+            RoadSide::YNegative => RoadSide::YPositive,
+            RoadSide::YPositive => RoadSide::YNegative,
+        }
+    }
+
+    /// Splits a point into how far it is towards this side and how far
+    /// across from the line through the center towards this side.
+    fn split(self, (x, y): (i32, i32)) -> (i32, i32) {
+        match self {
+            RoadSide::XNegative => (-x, y),
+            RoadSide::XPositive => (x, y),
+            RoadSide::YNegative => (-y, x),
+            RoadSide::YPositive => (y, x),
+        }
+    }
+}
+
+/// A square road piece. Roads run from the center to each of its open sides,
+/// and sidewalks fill the rest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoadPiece {
+    /// Half the side length, in Brickadia units.
+    pub size: u32,
+    pub profile: RoadProfile,
+    pub open: Vec<RoadSide>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Surface {
+    Sidewalk,
+    Road(RoadPaint),
+}
+
+impl RoadPiece {
+    pub fn new(size: u32, profile: RoadProfile, open: &[RoadSide]) -> Self {
+        Self {
+            size,
+            profile,
+            open: open.to_vec(),
+        }
+    }
+
+    fn is_open(&self, side: RoadSide) -> bool {
+        self.open.contains(&side)
+    }
+
+    /// Whether cars coming from this side stop at a line before the middle.
+    /// Crossings stop every side, junctions only the side road.
+    fn has_stop_line(&self, side: RoadSide) -> bool {
+        match self.open.len() {
+            4 => true,
+            3 => !self.is_open(side.opposite()),
+            _ => false,
+        }
+    }
+
+    /// What the piece is made of at a point.
+    fn surface(&self, point: (i32, i32)) -> Surface {
+        let half_width = self.profile.half_width();
+        let stripe_start = half_width - self.profile.stripe as i32;
+        let center = (self.profile.center_line / 2) as i32;
+        let in_stripe = |across: i32| across.abs() > stripe_start;
+
+        let outside: Vec<_> = RoadSide::ALL
+            .iter()
+            .copied()
+            .filter(|side| side.split(point).0 > half_width)
+            .collect();
+
+        match outside[..] {
+            // The road towards one side
+            [side] if self.is_open(side) => {
+                let (along, across) = side.split(point);
+                let center_start = if self.has_stop_line(side) {
+                    half_width + self.profile.stripe as i32 / 2
+                } else {
+                    half_width
+                };
+                Surface::Road(if in_stripe(across) {
+                    RoadPaint::Stripe
+                } else if across.abs() < center && along > center_start {
+                    RoadPaint::CenterLine
+                } else {
+                    RoadPaint::Asphalt
+                })
+            }
+            [] if !self.open.is_empty() => {
+                // Where the roads meet
+                let edges: Vec<_> = RoadSide::ALL
+                    .iter()
+                    .copied()
+                    .filter(|side| in_stripe(side.split(point).0) && side.split(point).0 > 0)
+                    .collect();
+                let stripe = edges.len() == 2
+                    || edges
+                        .iter()
+                        .any(|&side| !self.is_open(side) || self.has_stop_line(side));
+                let center_line = self.open.iter().any(|&side| {
+                    let (along, across) = side.split(point);
+                    !self.has_stop_line(side) && across.abs() < center && along > -center
+                });
+
+                Surface::Road(if stripe {
+                    RoadPaint::Stripe
+                } else if center_line {
+                    RoadPaint::CenterLine
+                } else {
+                    RoadPaint::Asphalt
+                })
+            }
+            _ => Surface::Sidewalk,
+        }
+    }
+
+    /// Builds the piece from as few rectangles as it easily can.
+    pub fn mapping(&self) -> BrickMapping {
+        let size = self.size as i32;
+        let half_width = self.profile.half_width();
+        let center = (self.profile.center_line / 2) as i32;
+        let stripe_start = half_width - self.profile.stripe as i32;
+        let center_start = half_width + self.profile.stripe as i32 / 2;
+
+        // Everything lines up with these
+        let mut cuts: Vec<i32> = [size, half_width, stripe_start, center, center_start]
+            .iter()
+            .flat_map(|&c| [-c, c])
+            .filter(|c| c.abs() <= size)
+            .collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let cells = cuts.len() - 1;
+        let surface_at = |i: usize, j: usize| {
+            self.surface(((cuts[i] + cuts[i + 1]) / 2, (cuts[j] + cuts[j + 1]) / 2))
+        };
+
+        merge_cells(cells, surface_at, |_| true)
+            .into_iter()
+            .map(|(i, end, j, top, surface)| {
+                surface_desc(surface, "PB_DefaultBrick", "PB_DefaultMicroBrick")
+                    .size((
+                        (cuts[end] - cuts[i]) as u32 / 2,
+                        (cuts[top] - cuts[j]) as u32 / 2,
+                        ROAD_HEIGHT,
+                    ))
+                    .offset(((cuts[i] + cuts[end]) / 2, (cuts[j] + cuts[top]) / 2, 0))
+                    .rotation_offset(0)
+            })
+            .collect()
+    }
+}
+
+/// Groups a grid of cells into rectangles of equal values: runs along X in
+/// each row, grown along Y while the next rows match. Cells that aren't
+/// `mergeable` stay on their own. Rectangles are given as the first and past
+/// the last cell along X and Y.
+fn merge_cells<T: PartialEq>(
+    cells: usize,
+    value_at: impl Fn(usize, usize) -> T,
+    mergeable: impl Fn(&T) -> bool,
+) -> Vec<(usize, usize, usize, usize, T)> {
+    let mut used = vec![vec![false; cells]; cells];
+    let mut rectangles = vec![];
+    for j in 0..cells {
+        let mut i = 0;
+        while i < cells {
+            if used[j][i] {
+                i += 1;
+                continue;
+            }
+            let value = value_at(i, j);
+            let (mut end, mut top) = (i + 1, j + 1);
+            if mergeable(&value) {
+                while end < cells && !used[j][end] && value_at(end, j) == value {
+                    end += 1;
+                }
+                while top < cells && (i..end).all(|k| !used[top][k] && value_at(k, top) == value) {
+                    top += 1;
+                }
+            }
+            for row in used.iter_mut().take(top).skip(j) {
+                row[i..end].iter_mut().for_each(|cell| *cell = true);
+            }
+            rectangles.push((i, end, j, top, value));
+            i = end;
+        }
+    }
+    rectangles
+}
+
+/// A brick of a road surface, from the sidewalk or road asset.
+fn surface_desc(surface: Surface, sidewalk: &'static str, road: &'static str) -> BrickDesc {
+    match surface {
+        Surface::Sidewalk => BrickDesc::new(sidewalk),
+        Surface::Road(paint) => BrickDesc::new(road).paint(paint),
+    }
+}
+
+/// What a road is made of at a distance across it from the center line.
+fn surface_across(profile: RoadProfile, across: f32) -> Surface {
+    let half_width = profile.half_width() as f32;
+    let stripe_start = half_width - profile.stripe as f32;
+    let center = (profile.center_line / 2) as f32;
+
+    let across = across.abs();
+    if across > half_width {
+        Surface::Sidewalk
+    } else if across > stripe_start {
+        Surface::Road(RoadPaint::Stripe)
+    } else if across < center {
+        Surface::Road(RoadPaint::CenterLine)
+    } else {
+        Surface::Road(RoadPaint::Asphalt)
+    }
+}
+
+/// Builds a road running from corner to corner. The piece is cut into cells,
+/// and cells the edges of the road or its markings run through are split
+/// along their diagonal into two side wedges.
+fn diagonal_mapping(size: u32, profile: RoadProfile) -> BrickMapping {
+    let size = size as i32;
+    let cells = (2 * size / DIAGONAL_STEP) as usize;
+    let start = |i: usize| -size + i as i32 * DIAGONAL_STEP;
+    let surface_at =
+        |(x, y): (f32, f32)| surface_across(profile, (x - y) / std::f32::consts::SQRT_2);
+
+    // The surfaces above and below the diagonal of each cell
+    let halves_at = |i: usize, j: usize| {
+        let (x0, y0) = (start(i) as f32, start(j) as f32);
+        let third = DIAGONAL_STEP as f32 / 3.0;
+        (
+            surface_at((x0 + third, y0 + 2.0 * third)),
+            surface_at((x0 + 2.0 * third, y0 + third)),
+        )
+    };
+
+    let mut mapping = vec![];
+    for (i, end, j, top, (upper, lower)) in merge_cells(cells, halves_at, |(a, b)| a == b) {
+        let (x0, x1, y0, y1) = (start(i), start(end), start(j), start(top));
+        let size = ((x1 - x0) as u32 / 2, (y1 - y0) as u32 / 2, ROAD_HEIGHT);
+        let offset = ((x0 + x1) / 2, (y0 + y1) / 2, 0);
+
+        if upper == lower {
+            mapping.push(
+                surface_desc(upper, "PB_DefaultBrick", "PB_DefaultMicroBrick")
+                    .size(size)
+                    .offset(offset)
+                    .rotation_offset(0),
+            );
+            continue;
+        }
+
+        // Side wedges turned to leave out the corner across the diagonal
+        for (surface, rotation) in [(upper, 1), (lower, 3)] {
+            mapping.push(
+                surface_desc(surface, "PB_DefaultSideWedge", "PB_DefaultSideWedge")
+                    .size(size)
+                    .offset(offset)
+                    .rotation_offset(rotation),
+            );
+        }
+    }
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// The area a mapping covers from above, in square Brickadia units.
+    fn area(mapping: &BrickMapping) -> u32 {
+        mapping
+            .iter()
+            .map(|desc| {
+                let area = 4 * desc.size.0 * desc.size.1;
+                if desc.asset == "PB_DefaultSideWedge" {
+                    area / 2
+                } else {
+                    area
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn every_piece_matches_the_pattern() {
+        let pattern = Regex::new(&road_pattern()).unwrap();
+        for (name, ..) in ROAD_PIECES {
+            assert!(pattern.is_match(name), "{name}");
+            assert!(road_mapping(name).is_some(), "{name}");
+        }
+        assert!(!pattern.is_match("32x32 Road Extra"));
+        assert!(road_mapping("32x32 Road Extra").is_none());
+    }
+
+    #[test]
+    fn pieces_without_roads_are_one_brick() {
+        let mapping = RoadLayout::Sides(80, &[]).mapping(ROAD_PROFILE);
+        assert_eq!(mapping.len(), 1);
+        assert_eq!(mapping[0].asset, "PB_DefaultBrick");
+        assert_eq!(mapping[0].size, (80, 80, ROAD_HEIGHT));
+        assert_eq!(mapping[0].paint, None);
+    }
+
+    #[test]
+    fn straight_roads_are_painted() {
+        let mapping = road_mapping("32x32 Road").unwrap();
+        for paint in [RoadPaint::Asphalt, RoadPaint::Stripe, RoadPaint::CenterLine] {
+            assert!(
+                mapping.iter().any(|desc| desc.paint == Some(paint)),
+                "{paint:?}"
+            );
+        }
+        assert_eq!(area(&mapping), 320 * 320);
+    }
+
+    #[test]
+    fn diagonals_cover_the_piece_with_side_wedges() {
+        let mapping = RoadLayout::Diagonal(160).mapping(ROAD_PROFILE);
+        let wedges: Vec<_> = mapping
+            .iter()
+            .filter(|desc| desc.asset == "PB_DefaultSideWedge")
+            .collect();
+        assert!(!wedges.is_empty());
+        assert!(wedges
+            .iter()
+            .all(|desc| [1, 3].contains(&desc.rotation_offset)));
+        assert_eq!(area(&mapping), 320 * 320);
+
+        // Cells on the center line are road, cells in the far corners sidewalk
+        let covers = |desc: &&BrickDesc, (x, y): (i32, i32)| {
+            (x - desc.offset.0).unsigned_abs() < desc.size.0
+                && (y - desc.offset.1).unsigned_abs() < desc.size.1
+        };
+        let corner: Vec<_> = mapping
+            .iter()
+            .filter(|desc| covers(desc, (-155, 155)))
+            .collect();
+        assert_eq!(corner.len(), 1);
+        assert_eq!(corner[0].asset, "PB_DefaultBrick");
+        assert!(mapping
+            .iter()
+            .any(|desc| desc.paint == Some(RoadPaint::CenterLine)));
+    }
+}
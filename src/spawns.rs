@@ -0,0 +1,201 @@
+//! Bricks that players or vehicles spawn at.
+
+use crate::{
+    extra::{self, find_extra, split_datablock},
+    map_position,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnKind {
+    Player,
+    Vehicle,
+    Checkpoint,
+}
+
+impl SpawnKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SpawnKind::Player => "player",
+            SpawnKind::Vehicle => "vehicle",
+            SpawnKind::Checkpoint => "checkpoint",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spawn {
+    pub kind: SpawnKind,
+    /// Index of the Blockland brick.
+    pub source: usize,
+    /// Center of the Blockland brick, in Brickadia units.
+    pub position: (i32, i32, i32),
+    /// Quarter turns around Z, like brick rotations.
+    pub rotation: u8,
+    /// The vehicle a vehicle spawn holds.
+    pub vehicle: Option<SpawnVehicle>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnVehicle {
+    /// Name of the vehicle datablock, as Blockland saves it, like `Jeep`.
+    pub datablock: String,
+    /// The color of the spawn brick, if the vehicle is painted in it.
+    pub color: Option<brs::Color>,
+    /// The Brickadia vehicle prefab or placeholder for the datablock, from
+    /// [`ConvertOptions::vehicles`](crate::ConvertOptions::vehicles).
+    pub prefab: Option<String>,
+}
+
+/// Which kind of spawn a brick is, by its ui_name.
+pub(crate) fn spawn_kind(ui_name: &str) -> Option<SpawnKind> {
+    match ui_name {
+        "Spawn Point" => Some(SpawnKind::Player),
+        "Vehicle Spawn" => Some(SpawnKind::Vehicle),
+        "Checkpoint" => Some(SpawnKind::Checkpoint),
+        _ => None,
+    }
+}
+
+/// Reads the spawn a brick stands for, if it's a spawn brick.
+///
+/// Vehicles are looked up in `prefabs`, and painted vehicles take the
+/// brick's color from `colors`.
+pub(crate) fn brick_spawn(
+    source: usize,
+    brick: &bl_save::Brick,
+    colors: &[brs::Color],
+    prefabs: &HashMap<String, String>,
+) -> Option<Spawn> {
+    let kind = spawn_kind(&brick.base.ui_name)?;
+
+    let vehicle = match kind {
+        SpawnKind::Vehicle => find_extra(brick, extra::VEHICLE).and_then(|value| {
+            // Like `Jeep" 1`, where 1 paints the vehicle in the brick's color
+            let (datablock, recolor) = split_datablock(value);
+            if datablock.is_empty() {
+                return None;
+            }
+            Some(SpawnVehicle {
+                datablock: datablock.to_string(),
+                color: match recolor.trim() {
+                    "1" => colors.get(usize::from(brick.base.color_index)).copied(),
+                    _ => None,
+                },
+                prefab: prefabs.get(datablock).cloned(),
+            })
+        }),
+        _ => None,
+    };
+
+    Some(Spawn {
+        kind,
+        source,
+        position: map_position(brick.base.position),
+        rotation: brick.base.angle,
+        vehicle,
+    })
+}
+
+impl Spawn {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.name(),
+            "source": self.source,
+            "position": self.position,
+            "rotation": self.rotation,
+            "vehicle": self.vehicle.as_ref().map(SpawnVehicle::to_json),
+        })
+    }
+}
+
+impl SpawnVehicle {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "datablock": self.datablock,
+            "color": self.color.map(|color| format!("{:?}", color)),
+            "prefab": self.prefab,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn colors() -> Vec<brs::Color> {
+        (0..8).map(|i| brs::Color::from_rgba(i, 0, 0, 255)).collect()
+    }
+
+    #[test]
+    fn reads_spawn_kinds_and_facing() {
+        let brick = test_util::brick(&["Spawn Point\" 1 2 0.3 3 0 0  0 0 1 1 1"]);
+        let spawn = brick_spawn(4, &brick, &colors(), &HashMap::new()).unwrap();
+        assert_eq!(spawn.kind, SpawnKind::Player);
+        assert_eq!(spawn.source, 4);
+        assert_eq!(spawn.position, (40, 20, 6));
+        assert_eq!(spawn.rotation, 3);
+        assert_eq!(spawn.vehicle, None);
+
+        let brick = test_util::brick(&["Checkpoint\" 0 0 0.3 0 0 0  0 0 1 1 1"]);
+        let spawn = brick_spawn(0, &brick, &colors(), &HashMap::new()).unwrap();
+        assert_eq!(spawn.kind, SpawnKind::Checkpoint);
+
+        let brick = test_util::brick(&["2x4\" 0 0 0.3 0 0 0  0 0 1 1 1"]);
+        assert!(brick_spawn(0, &brick, &colors(), &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn carries_vehicles_over() {
+        let prefabs: HashMap<_, _> = [("Jeep".to_string(), "BV_Jeep".to_string())].into();
+
+        let brick = test_util::brick(&[
+            "Vehicle Spawn\" 0 0 0.3 0 0 5  0 0 1 1 1",
+            "+-VEHICLE Jeep\" 1",
+        ]);
+        let vehicle = brick_spawn(0, &brick, &colors(), &prefabs).unwrap().vehicle.unwrap();
+        assert_eq!(vehicle.datablock, "Jeep");
+        assert_eq!(vehicle.color, Some(brs::Color::from_rgba(5, 0, 0, 255)));
+        assert_eq!(vehicle.prefab.as_deref(), Some("BV_Jeep"));
+
+        let brick = test_util::brick(&[
+            "Vehicle Spawn\" 0 0 0.3 0 0 5  0 0 1 1 1",
+            "+-VEHICLE Horse\" 0",
+        ]);
+        let vehicle = brick_spawn(0, &brick, &colors(), &prefabs).unwrap().vehicle.unwrap();
+        assert_eq!(vehicle.color, None);
+        assert_eq!(vehicle.prefab, None);
+
+        let brick = test_util::brick(&["Vehicle Spawn\" 0 0 0.3 0 0 5  0 0 1 1 1"]);
+        let spawn = brick_spawn(0, &brick, &colors(), &prefabs).unwrap();
+        assert_eq!(spawn.kind, SpawnKind::Vehicle);
+        assert_eq!(spawn.vehicle, None);
+    }
+
+    #[test]
+    fn writes_json() {
+        let spawn = Spawn {
+            kind: SpawnKind::Vehicle,
+            source: 2,
+            position: (0, 10, 6),
+            rotation: 1,
+            vehicle: Some(SpawnVehicle {
+                datablock: "Jeep".to_string(),
+                color: None,
+                prefab: Some("BV_Jeep".to_string()),
+            }),
+        };
+        assert_eq!(
+            spawn.to_json(),
+            json!({
+                "kind": "vehicle",
+                "source": 2,
+                "position": [0, 10, 6],
+                "rotation": 1,
+                "vehicle": { "datablock": "Jeep", "color": null, "prefab": "BV_Jeep" },
+            })
+        );
+    }
+}
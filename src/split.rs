@@ -0,0 +1,243 @@
+use crate::{bounds::Bounds, table::Table};
+use std::collections::{BTreeMap, HashMap};
+
+/// How to partition a converted save into several saves.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitOptions {
+    /// Side length of the square ground tiles, in Brickadia units.
+    pub tile_size: Option<u32>,
+    /// The most bricks a single save may hold. Fuller tiles are split into parts.
+    pub max_bricks: Option<usize>,
+}
+
+/// One piece of a split save.
+pub struct Tile {
+    /// Grid coordinates of the tile, `(0, 0)` when not splitting by size.
+    pub x: i32,
+    pub y: i32,
+    /// Which part of the tile this is, if the tile held too many bricks.
+    pub part: Option<usize>,
+    pub write_data: brs::WriteData,
+}
+
+/// A set of bricks converted from the same Blockland brick.
+struct Group {
+    bricks: Vec<usize>,
+    bounds: Bounds,
+}
+
+/// Partitions a converted save into tiles, each with its own trimmed tables.
+///
+/// `brick_sources` comes from the conversion report. Bricks converted from the
+/// same Blockland brick are placed by their combined center and never separated.
+pub fn split(
+    write_data: &brs::WriteData,
+    brick_sources: &[usize],
+    options: SplitOptions,
+) -> Vec<Tile> {
+    let mut groups: Vec<Group> = vec![];
+    let mut group_of_source: HashMap<usize, usize> = HashMap::new();
+
+    for (index, brick) in write_data.bricks.iter().enumerate() {
+        let asset = &write_data.brick_assets[brick.asset_name_index as usize];
+        let bounds = Bounds::of_brick(asset, brick);
+        let source = brick_sources.get(index).copied().unwrap_or(usize::MAX - index);
+
+        match group_of_source.get(&source) {
+            Some(&group_index) => {
+                let group = &mut groups[group_index];
+                group.bricks.push(index);
+                group.bounds = group.bounds.union(&bounds);
+            }
+            None => {
+                group_of_source.insert(source, groups.len());
+                groups.push(Group {
+                    bricks: vec![index],
+                    bounds,
+                });
+            }
+        }
+    }
+
+    let mut tiles: BTreeMap<(i32, i32), Vec<usize>> = BTreeMap::new();
+
+    for (group_index, group) in groups.iter().enumerate() {
+        let coords = match options.tile_size {
+            Some(tile_size) => {
+                let (x, y, _) = group.bounds.center();
+                (x.div_euclid(tile_size as i32), y.div_euclid(tile_size as i32))
+            }
+            None => (0, 0),
+        };
+        tiles.entry(coords).or_default().push(group_index);
+    }
+
+    let mut result = vec![];
+
+    for ((x, y), tile_groups) in tiles {
+        let parts = match options.max_bricks {
+            Some(max_bricks) => partition(&groups, tile_groups, max_bricks.max(1)),
+            None => vec![tile_groups],
+        };
+        let split_into_parts = parts.len() > 1;
+
+        for (part, part_groups) in parts.into_iter().enumerate() {
+            let mut bricks: Vec<_> = part_groups
+                .iter()
+                .flat_map(|g| groups[*g].bricks.iter().copied())
+                .collect();
+            bricks.sort_unstable();
+
+            result.push(Tile {
+                x,
+                y,
+                part: if split_into_parts { Some(part) } else { None },
+                write_data: extract(write_data, &bricks),
+            });
+        }
+    }
+
+    result
+}
+
+/// Recursively halves a set of groups along its longer axis until every part
+/// holds at most `max_bricks` bricks (or a single group).
+fn partition(groups: &[Group], mut part: Vec<usize>, max_bricks: usize) -> Vec<Vec<usize>> {
+    let brick_count: usize = part.iter().map(|g| groups[*g].bricks.len()).sum();
+
+    if brick_count <= max_bricks || part.len() < 2 {
+        return vec![part];
+    }
+
+    let centers = part.iter().map(|g| groups[*g].bounds.center());
+    let (min_x, max_x, min_y, max_y) = centers.fold(
+        (i32::MAX, i32::MIN, i32::MAX, i32::MIN),
+        |(min_x, max_x, min_y, max_y), (x, y, _)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    );
+
+    if max_x - min_x >= max_y - min_y {
+        part.sort_by_key(|g| groups[*g].bounds.center().0);
+    } else {
+        part.sort_by_key(|g| groups[*g].bounds.center().1);
+    }
+
+    let mut seen = 0;
+    let middle = part
+        .iter()
+        .position(|g| {
+            seen += groups[*g].bricks.len();
+            seen * 2 >= brick_count
+        })
+        .map(|i| (i + 1).min(part.len() - 1))
+        .unwrap_or(1);
+
+    let rest = part.split_off(middle);
+    let mut parts = partition(groups, part, max_bricks);
+    parts.append(&mut partition(groups, rest, max_bricks));
+    parts
+}
+
+/// Copies the given bricks into new save data holding only the table entries they use.
+fn extract(from: &brs::WriteData, bricks: &[usize]) -> brs::WriteData {
+    let mut data = brs::WriteData {
+        map: from.map.clone(),
+        author: from.author.clone(),
+        description: from.description.clone(),
+        save_time: from.save_time,
+        mods: from.mods.clone(),
+        brick_assets: vec![],
+        colors: vec![],
+        materials: vec![],
+        brick_owners: vec![],
+        bricks: Vec::with_capacity(bricks.len()),
+    };
+
+    let mut colors = Table::new(&mut data.colors, |c| -> u32 { (*c).into() });
+    let mut assets = Table::new(&mut data.brick_assets, String::clone);
+    let mut materials = Table::new(&mut data.materials, String::clone);
+    let mut owners = Table::new(&mut data.brick_owners, |u| (u.id, u.name.clone()));
+
+    for &index in bricks {
+        let mut brick = from.bricks[index].clone();
+        brick.asset_name_index =
+            assets.index(from.brick_assets[brick.asset_name_index as usize].clone());
+        brick.material_index =
+            materials.index(from.materials[brick.material_index as usize].clone());
+        brick.owner_index = owners.index(from.brick_owners[brick.owner_index as usize].clone());
+        if let brs::ColorMode::Set(color) = brick.color {
+            brick.color = brs::ColorMode::Set(colors.index(from.colors[color as usize]));
+        }
+        data.bricks.push(brick);
+    }
+
+    // The brs writer can't encode indices into a color table with fewer than two entries.
+    while data.colors.len() < 2 {
+        let padding = data.colors.first().copied();
+        data.colors.push(padding.unwrap_or_else(|| brs::Color::from_rgba(0, 0, 0, 255)));
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    fn positions(tile: &Tile) -> Vec<(i32, i32, i32)> {
+        tile.write_data.bricks.iter().map(|b| b.position).collect()
+    }
+
+    #[test]
+    fn splits_into_tiles_by_center() {
+        let converted = convert(
+            &[
+                r#"2x2" 1 1 0.3 0 0 3  0 0 1 1 1"#,
+                r#"2x2" 30 1 0.3 0 0 3  0 0 1 1 1"#,
+                r#"2x2" -30 1 0.3 0 0 3  0 0 1 1 1"#,
+            ],
+            &ConvertOptions::default(),
+        );
+        let options = SplitOptions {
+            tile_size: Some(200),
+            max_bricks: None,
+        };
+        let tiles = split(&converted.write_data, &converted.brick_sources, options);
+
+        let coords: Vec<_> = tiles.iter().map(|t| (t.x, t.y, t.part)).collect();
+        assert_eq!(coords, vec![(0, -3, None), (0, 0, None), (0, 3, None)]);
+        assert_eq!(positions(&tiles[1]), vec![(20, 20, 6)]);
+        for tile in &tiles {
+            assert_eq!(tile.write_data.brick_assets, vec!["PB_DefaultBrick"]);
+        }
+    }
+
+    #[test]
+    fn keeps_bricks_of_one_source_together() {
+        // A cone is one brick, a house door several
+        let converted = convert(
+            &[
+                r#"House Door" 0 0 1.2 0 0 2  0 0 1 1 1"#,
+                r#"1x1 Cone" 10 10 0.6 0 0 2  0 0 1 1 1"#,
+                r#"1x1 Cone" 20 20 0.6 0 0 2  0 0 1 1 1"#,
+            ],
+            &ConvertOptions::default(),
+        );
+        let door_bricks = converted.brick_sources.iter().filter(|s| **s == 0).count();
+        assert!(door_bricks > 1);
+
+        let options = SplitOptions {
+            tile_size: None,
+            max_bricks: Some(1),
+        };
+        let tiles = split(&converted.write_data, &converted.brick_sources, options);
+
+        assert_eq!(tiles.len(), 3);
+        let mut sizes: Vec<_> = tiles.iter().map(|t| t.write_data.bricks.len()).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, door_bricks]);
+        assert!(tiles.iter().all(|t| t.part.is_some()));
+    }
+}
@@ -0,0 +1,52 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A lookup table that only grows when a new value is added.
+pub(crate) struct Table<'a, T, K, F> {
+    values: &'a mut Vec<T>,
+    indices: HashMap<K, u32>,
+    key: F,
+}
+
+impl<'a, T, K: Hash + Eq, F: Fn(&T) -> K> Table<'a, T, K, F> {
+    pub(crate) fn new(values: &'a mut Vec<T>, key: F) -> Self {
+        let indices = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (key(v), i as u32))
+            .collect();
+        Self {
+            values,
+            indices,
+            key,
+        }
+    }
+
+    pub(crate) fn index(&mut self, value: T) -> u32 {
+        let key = (self.key)(&value);
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+
+        let index = self.values.len() as u32;
+        self.values.push(value);
+        self.indices.insert(key, index);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_adds_new_values() {
+        let mut values = vec!["a".to_string(), "b".to_string()];
+        let mut table = Table::new(&mut values, |value: &String| value.to_lowercase());
+
+        assert_eq!(table.index("b".to_string()), 1);
+        assert_eq!(table.index("c".to_string()), 2);
+        assert_eq!(table.index("A".to_string()), 0);
+        assert_eq!(table.index("C".to_string()), 2);
+        assert_eq!(values, ["a", "b", "c"]);
+    }
+}
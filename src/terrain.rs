@@ -0,0 +1,740 @@
+//! Terrain mode for ModTer bricks, which turns slopes and corners to fit the
+//! bricks around them and can merge runs of them into longer bricks.
+
+use crate::{
+    bounds::world_half_extents,
+    limits::MAX_BRICK_EXTENT,
+    mesh::{brick_mesh, Mesh},
+};
+use brs::Direction::*;
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Settings for [`ConvertOptions::terrain`](crate::ConvertOptions::terrain).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainOptions {
+    /// Whether to merge runs of matching slopes and cubes into longer bricks.
+    pub merge: bool,
+    /// Largest half extent a merged brick grows to, in Brickadia units.
+    pub max_merged_extent: u32,
+}
+
+impl Default for TerrainOptions {
+    fn default() -> Self {
+        Self {
+            merge: false,
+            max_merged_extent: MAX_BRICK_EXTENT,
+        }
+    }
+}
+
+/// What terrain mode changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerrainReport {
+    /// Slopes and corners replaced or turned to fit their neighbours.
+    pub refitted: usize,
+    /// Bricks saved by merging runs.
+    pub merged: usize,
+}
+
+/// A converted ModTer brick.
+#[derive(Debug, Clone)]
+pub(crate) struct TerrainBrick {
+    pub asset: &'static str,
+    pub brick: brs::Brick,
+    /// Index of the Blockland brick.
+    pub source: usize,
+}
+
+/// Slopes and corners a ModTer brick can be swapped for.
+const SHAPES: [&str; 3] = [
+    "PB_DefaultMicroWedge",
+    "PB_DefaultMicroWedgeOuterCorner",
+    "PB_DefaultMicroWedgeInnerCorner",
+];
+
+const DIRECTIONS: [brs::Direction; 6] =
+    [XPositive, XNegative, YPositive, YNegative, ZPositive, ZNegative];
+
+/// Where a brick is, seen from above, and the heights it spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Footprint {
+    min: (i32, i32),
+    max: (i32, i32),
+    bottom: i32,
+    top: i32,
+}
+
+impl Footprint {
+    fn of_mesh(mesh: &Mesh) -> Option<Self> {
+        let mut vertices = mesh
+            .vertices
+            .iter()
+            .map(|v| (v[0].round() as i32, v[1].round() as i32, v[2].round() as i32));
+        let first = vertices.next()?;
+        Some(vertices.fold(
+            Footprint {
+                min: (first.0, first.1),
+                max: (first.0, first.1),
+                bottom: first.2,
+                top: first.2,
+            },
+            |f, (x, y, z)| Footprint {
+                min: (f.min.0.min(x), f.min.1.min(y)),
+                max: (f.max.0.max(x), f.max.1.max(y)),
+                bottom: f.bottom.min(z),
+                top: f.top.max(z),
+            },
+        ))
+    }
+
+    /// Ordered `(-x, -y)`, `(x, -y)`, `(x, y)`, `(-x, y)`.
+    fn corners(&self) -> [(i32, i32); 4] {
+        [
+            (self.min.0, self.min.1),
+            (self.max.0, self.min.1),
+            (self.max.0, self.max.1),
+            (self.min.0, self.max.1),
+        ]
+    }
+
+    /// Whether the two are side by side, not on top of each other or inside
+    /// each other. Bricks touching only at a corner count.
+    fn beside(&self, other: &Footprint) -> bool {
+        let apart = self.min.0 >= other.max.0
+            || other.min.0 >= self.max.0
+            || self.min.1 >= other.max.1
+            || other.min.1 >= self.max.1;
+        apart && self.bottom < other.top && other.bottom < self.top
+    }
+}
+
+/// The lowest and highest point of a mesh straight above a corner, if the
+/// mesh reaches that corner at all.
+fn heights_at(mesh: &Mesh, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+    let mut heights = mesh
+        .vertices
+        .iter()
+        .filter(|v| (v[0].round() as i32, v[1].round() as i32) == (x, y))
+        .map(|v| v[2].round() as i32);
+    let first = heights.next()?;
+    Some(heights.fold((first, first), |(low, high), z| (low.min(z), high.max(z))))
+}
+
+/// The side of a slope or corner that slopes. Inverted pieces slope on their
+/// bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Top,
+    Bottom,
+}
+
+/// The shape of a slope or corner: its sloped side, and at which corners of
+/// its footprint, ordered like [`Footprint::corners`], that side reaches all
+/// the way across the brick's height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Profile {
+    side: Side,
+    full: [bool; 4],
+}
+
+impl Profile {
+    /// Reads the shape of a brick from its geometry, however it's turned.
+    /// Cubes, and wedges standing upright, which don't reach every corner,
+    /// have none.
+    fn of_mesh(mesh: &Mesh, footprint: &Footprint) -> Option<Self> {
+        let mut heights = [(0, 0); 4];
+        for (corner, heights) in footprint.corners().iter().zip(&mut heights) {
+            *heights = heights_at(mesh, *corner)?;
+        }
+
+        let (bottom, top) = (footprint.bottom, footprint.top);
+        let at_an_end = |z: i32| z == bottom || z == top;
+        let flat_bottom = heights.iter().all(|&(low, high)| low == bottom && at_an_end(high));
+        let flat_top = heights.iter().all(|&(low, high)| high == top && at_an_end(low));
+        let (side, full) = if flat_bottom {
+            (Side::Top, heights.map(|(_, high)| high == top))
+        } else if flat_top {
+            (Side::Bottom, heights.map(|(low, _)| low == bottom))
+        } else {
+            return None;
+        };
+
+        if full == [true; 4] {
+            None
+        } else {
+            Some(Profile { side, full })
+        }
+    }
+}
+
+/// How a brick is placed, apart from where.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Placement {
+    asset: &'static str,
+    size: (u32, u32, u32),
+    direction: brs::Direction,
+    rotation: brs::Rotation,
+}
+
+impl Placement {
+    fn of(terrain: &TerrainBrick) -> Self {
+        Placement {
+            asset: terrain.asset,
+            size: terrain.brick.size,
+            direction: terrain.brick.direction,
+            rotation: terrain.brick.rotation,
+        }
+    }
+
+    fn apply(self, terrain: &mut TerrainBrick) {
+        terrain.asset = self.asset;
+        terrain.brick.size = self.size;
+        terrain.brick.direction = self.direction;
+        terrain.brick.rotation = self.rotation;
+    }
+
+    fn mesh_at_origin(self) -> Mesh {
+        let brick = brs::Brick {
+            asset_name_index: 0,
+            size: self.size,
+            position: (0, 0, 0),
+            direction: self.direction,
+            rotation: self.rotation,
+            collision: true,
+            visibility: true,
+            material_index: 0,
+            color: brs::ColorMode::Set(0),
+            owner_index: 0,
+        };
+        brick_mesh(self.asset, &brick)
+    }
+}
+
+/// Every shape a slope or corner can take in the same space, sloped on the
+/// same side, and how to place a brick to get it. The brick's own placement
+/// comes first, and other placements of its own asset before [`SHAPES`].
+fn variants(placement: Placement) -> Vec<(Profile, Placement)> {
+    let mesh = placement.mesh_at_origin();
+    let footprint = match Footprint::of_mesh(&mesh) {
+        Some(footprint) => footprint,
+        None => return vec![],
+    };
+    let profile = match Profile::of_mesh(&mesh, &footprint) {
+        Some(profile) => profile,
+        None => return vec![],
+    };
+
+    let (x, y, z) = placement.size;
+    let sizes = [(x, y, z), (x, z, y), (y, x, z), (y, z, x), (z, x, y), (z, y, x)];
+    let mut variants = vec![(profile, placement)];
+    for asset in std::iter::once(placement.asset).chain(SHAPES) {
+        for (size, direction, rotation) in sizes
+            .iter()
+            .flat_map(|&size| DIRECTIONS.iter().map(move |&direction| (size, direction)))
+            .flat_map(|(size, direction)| (0..4u8).map(move |rotation| (size, direction, rotation)))
+        {
+            let candidate = Placement {
+                asset,
+                size,
+                direction,
+                rotation: rotation.try_into().unwrap(),
+            };
+            let mesh = candidate.mesh_at_origin();
+            if Footprint::of_mesh(&mesh) != Some(footprint) {
+                continue;
+            }
+            if let Some(shape) = Profile::of_mesh(&mesh, &footprint) {
+                if shape.side == profile.side && variants.iter().all(|(seen, _)| *seen != shape) {
+                    variants.push((shape, candidate));
+                }
+            }
+        }
+    }
+    variants
+}
+
+/// The heights of a brick's top and bottom at one corner of its footprint.
+struct Surface {
+    /// Index of the terrain brick, or `None` for other bricks.
+    brick: Option<usize>,
+    footprint: Footprint,
+    bottom: i32,
+    top: i32,
+}
+
+type Surfaces = HashMap<(i32, i32), Vec<Surface>>;
+
+/// Adds the heights of a brick at the corners of its footprint.
+fn add_surfaces(surfaces: &mut Surfaces, brick: Option<usize>, asset: &str, from: &brs::Brick) {
+    let mesh = brick_mesh(asset, from);
+    let footprint = match Footprint::of_mesh(&mesh) {
+        Some(footprint) => footprint,
+        None => return,
+    };
+
+    for corner in footprint.corners().iter().copied() {
+        if let Some((bottom, top)) = heights_at(&mesh, corner) {
+            surfaces.entry(corner).or_default().push(Surface {
+                brick,
+                footprint,
+                bottom,
+                top,
+            });
+        }
+    }
+}
+
+/// Takes out the heights of a terrain brick, before it changes shape.
+fn remove_surfaces(surfaces: &mut Surfaces, brick: usize, footprint: &Footprint) {
+    for corner in footprint.corners().iter() {
+        if let Some(corner) = surfaces.get_mut(corner) {
+            corner.retain(|s| s.brick != Some(brick));
+        }
+    }
+}
+
+/// A slope or corner that can be turned or swapped.
+#[derive(Debug, Clone, Copy)]
+struct Piece {
+    footprint: Footprint,
+    profile: Profile,
+    /// The placement it was converted with, which its variants are kept by.
+    original: Placement,
+}
+
+impl Piece {
+    fn of(terrain: &TerrainBrick) -> Option<Self> {
+        let mesh = brick_mesh(terrain.asset, &terrain.brick);
+        let footprint = Footprint::of_mesh(&mesh)?;
+        Some(Piece {
+            footprint,
+            profile: Profile::of_mesh(&mesh, &footprint)?,
+            original: Placement::of(terrain),
+        })
+    }
+}
+
+/// How many times a brick may change as its neighbours do.
+const MAX_REFITS: usize = 4;
+
+/// A shape that fits a brick's neighbours better than the one it has.
+#[derive(Debug, Clone, Copy)]
+struct Fit {
+    /// How many of the neighbours' corners the brick lines up with as it is.
+    current: i32,
+    /// How many more it lines up with in the new shape.
+    gain: i32,
+    profile: Profile,
+    placement: Placement,
+}
+
+/// Finds a shape that fits the neighbours of a piece strictly better than
+/// its own.
+fn better_fit(
+    piece: &Piece,
+    index: usize,
+    variants: &[(Profile, Placement)],
+    surfaces: &Surfaces,
+) -> Option<Fit> {
+    let footprint = piece.footprint;
+
+    // Positive votes for the sloped side reaching across at the corner,
+    // negative for it not
+    let votes: Vec<i32> = footprint
+        .corners()
+        .iter()
+        .map(|corner| {
+            surfaces
+                .get(corner)
+                .into_iter()
+                .flatten()
+                .filter(|s| s.brick != Some(index) && s.footprint.beside(&footprint))
+                .map(|s| {
+                    let (height, full, empty) = match piece.profile.side {
+                        Side::Top => (s.top, footprint.top, footprint.bottom),
+                        Side::Bottom => (s.bottom, footprint.bottom, footprint.top),
+                    };
+                    if height == full {
+                        1
+                    } else if height == empty {
+                        -1
+                    } else {
+                        0
+                    }
+                })
+                .sum()
+        })
+        .collect();
+    let score = |full: [bool; 4]| -> i32 {
+        full.iter()
+            .zip(&votes)
+            .map(|(&full, &vote)| if full { vote } else { -vote })
+            .sum()
+    };
+
+    let current = score(piece.profile.full);
+    let mut best: Option<Fit> = None;
+    for &(profile, placement) in variants {
+        let gain = score(profile.full) - current;
+        if gain > best.map_or(0, |fit| fit.gain) {
+            best = Some(Fit {
+                current,
+                gain,
+                profile,
+                placement,
+            });
+        }
+    }
+    best
+}
+
+/// Turns and swaps slopes and corners so the ends of their sloped sides line
+/// up with the bricks beside them. Inverted pieces are fitted by their
+/// bottoms against the bottoms of their neighbours.
+///
+/// The brick that gains the most is fixed first, and the worst fitting one
+/// among equals, so that it doesn't mislead its neighbours. Then its
+/// neighbours are fitted again.
+fn refit(terrain: &mut [TerrainBrick], others: &[(&str, &brs::Brick)]) -> usize {
+    let original: Vec<_> = terrain.iter().map(Placement::of).collect();
+
+    let mut surfaces = Surfaces::new();
+    for (asset, brick) in others {
+        add_surfaces(&mut surfaces, None, asset, brick);
+    }
+    for (index, t) in terrain.iter().enumerate() {
+        add_surfaces(&mut surfaces, Some(index), t.asset, &t.brick);
+    }
+
+    let mut pieces: Vec<_> = terrain.iter().map(Piece::of).collect();
+    let mut variants = HashMap::new();
+    for piece in pieces.iter().flatten() {
+        variants
+            .entry(piece.original)
+            .or_insert_with(|| self::variants(piece.original));
+    }
+    let fit = |pieces: &[Option<Piece>], index: usize, surfaces: &Surfaces| {
+        let piece = pieces[index].as_ref()?;
+        better_fit(piece, index, &variants[&piece.original], surfaces)
+    };
+
+    let mut fits: Vec<_> = (0..terrain.len())
+        .map(|index| fit(&pieces, index, &surfaces))
+        .collect();
+    let mut refits = vec![0; terrain.len()];
+
+    while let Some(index) = (0..fits.len())
+        .filter(|&index| fits[index].is_some())
+        .max_by_key(|&index| {
+            let fit = fits[index].unwrap();
+            (fit.gain, Reverse(fit.current), Reverse(index))
+        })
+    {
+        let fit_found = fits[index].take().unwrap();
+        let piece = pieces[index].as_mut().unwrap();
+        let footprint = piece.footprint;
+
+        remove_surfaces(&mut surfaces, index, &footprint);
+        piece.profile = fit_found.profile;
+        let brick = &mut terrain[index];
+        fit_found.placement.apply(brick);
+        add_surfaces(&mut surfaces, Some(index), brick.asset, &brick.brick);
+        refits[index] += 1;
+
+        let neighbours: Vec<_> = footprint
+            .corners()
+            .iter()
+            .filter_map(|corner| surfaces.get(corner))
+            .flatten()
+            .filter_map(|s| s.brick)
+            .collect();
+        for neighbour in neighbours {
+            fits[neighbour] = if refits[neighbour] < MAX_REFITS {
+                fit(&pieces, neighbour, &surfaces)
+            } else {
+                None
+            };
+        }
+    }
+
+    terrain
+        .iter()
+        .zip(&original)
+        .filter(|(brick, original)| Placement::of(brick) != **original)
+        .count()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+}
+
+impl Axis {
+    fn of(self, (x, y, _): (i32, i32, i32)) -> i32 {
+        match self {
+            Axis::X => x,
+            Axis::Y => y,
+        }
+    }
+
+    fn across(self, (x, y, z): (i32, i32, i32)) -> (i32, i32) {
+        match self {
+            Axis::X => (y, z),
+            Axis::Y => (x, z),
+        }
+    }
+}
+
+/// If a brick looks the same everywhere along an axis, so that a run of them
+/// can be one longer brick, which part of its size is its half extent along
+/// that axis.
+fn merge_index(terrain: &TerrainBrick, axis: Axis) -> Option<usize> {
+    let mesh = brick_mesh(terrain.asset, &terrain.brick);
+    let points: Vec<_> = mesh
+        .vertices
+        .iter()
+        .map(|v| (v[0].round() as i32, v[1].round() as i32, v[2].round() as i32))
+        .collect();
+    let start = points.iter().map(|&p| axis.of(p)).min()?;
+    let end = points.iter().map(|&p| axis.of(p)).max()?;
+    let section = |at: i32| {
+        let mut section: Vec<_> = points
+            .iter()
+            .filter(|&&p| axis.of(p) == at)
+            .map(|&p| axis.across(p))
+            .collect();
+        section.sort_unstable();
+        section.dedup();
+        section
+    };
+    let inside = points.iter().any(|&p| axis.of(p) != start && axis.of(p) != end);
+    if inside || section(start) != section(end) {
+        return None;
+    }
+
+    let extent = |brick: &brs::Brick| {
+        let (x, y, z) = world_half_extents(terrain.asset, brick);
+        axis.of((x as i32, y as i32, z as i32))
+    };
+    (0..3).find(|&index| {
+        let mut grown = terrain.brick.clone();
+        match index {
+            0 => grown.size.0 += 1,
+            1 => grown.size.1 += 1,
+            _ => grown.size.2 += 1,
+        }
+        extent(&grown) != extent(&terrain.brick)
+    })
+}
+
+/// Merges runs of matching bricks next to each other along an axis.
+fn merge_along(terrain: Vec<TerrainBrick>, axis: Axis, max_extent: u32) -> Vec<TerrainBrick> {
+    let (mut runs, rest): (Vec<_>, Vec<_>) = terrain
+        .into_iter()
+        .map(|t| (merge_index(&t, axis), t))
+        .partition(|(index, _)| index.is_some());
+    runs.sort_by_key(|(_, t)| axis.of(t.brick.position));
+
+    let mut merged: Vec<TerrainBrick> = vec![];
+    let mut open = HashMap::new();
+    for (index, terrain) in runs {
+        let index = index.unwrap();
+        let brick = &terrain.brick;
+        let sizes = [brick.size.0, brick.size.1, brick.size.2];
+        let extent = sizes[index];
+        let color = match brick.color {
+            brs::ColorMode::Set(index) => (false, index),
+            brs::ColorMode::Custom(color) => (true, color.into()),
+        };
+        let key = (
+            terrain.asset,
+            brick.size,
+            brick.direction,
+            brick.rotation,
+            axis.across(brick.position),
+            color,
+            (brick.material_index, brick.owner_index),
+            (brick.collision, brick.visibility),
+        );
+
+        if let Some(&run) = open.get(&key) {
+            let run: &mut TerrainBrick = &mut merged[run];
+            let run_extent = [run.brick.size.0, run.brick.size.1, run.brick.size.2][index];
+            let run_start = axis.of(run.brick.position) - run_extent as i32;
+            let run_end = axis.of(run.brick.position) + run_extent as i32;
+            let along = axis.of(brick.position);
+            if run_end == along - extent as i32 && run_extent + extent <= max_extent {
+                let middle = (run_start + along + extent as i32) / 2;
+                match index {
+                    0 => run.brick.size.0 += extent,
+                    1 => run.brick.size.1 += extent,
+                    _ => run.brick.size.2 += extent,
+                }
+                match axis {
+                    Axis::X => run.brick.position.0 = middle,
+                    Axis::Y => run.brick.position.1 = middle,
+                }
+                continue;
+            }
+        }
+
+        open.insert(key, merged.len());
+        merged.push(terrain);
+    }
+
+    merged.extend(rest.into_iter().map(|(_, t)| t));
+    merged
+}
+
+/// Fits ModTer bricks to the bricks around them, and merges runs of them
+/// if `options.merge` is set. `others` are all the other converted bricks,
+/// which the terrain is fitted against but which stay as they are.
+pub(crate) fn build_terrain(
+    mut terrain: Vec<TerrainBrick>,
+    others: &[(&str, &brs::Brick)],
+    options: &TerrainOptions,
+) -> (Vec<TerrainBrick>, TerrainReport) {
+    let refitted = refit(&mut terrain, others);
+
+    let count = terrain.len();
+    if options.merge {
+        terrain = merge_along(terrain, Axis::X, options.max_merged_extent);
+        terrain = merge_along(terrain, Axis::Y, options.max_merged_extent);
+    }
+
+    let report = TerrainReport {
+        refitted,
+        merged: count - terrain.len(),
+    };
+    (terrain, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::convert, ConvertOptions};
+
+    /// Converts bricks in terrain mode, giving the converted bricks with
+    /// their assets.
+    fn build(lines: &[&str], merge: bool) -> (Vec<(String, brs::Brick)>, TerrainReport) {
+        let options = ConvertOptions {
+            terrain: Some(TerrainOptions {
+                merge,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let report = convert(lines, &options);
+        let data = report.write_data;
+        let bricks = data
+            .bricks
+            .into_iter()
+            .map(|brick| (data.brick_assets[brick.asset_name_index as usize].clone(), brick))
+            .collect();
+        (bricks, report.terrain)
+    }
+
+    fn shape(asset: &str, brick: &brs::Brick) -> Option<(Footprint, Profile)> {
+        let mesh = brick_mesh(asset, brick);
+        let footprint = Footprint::of_mesh(&mesh)?;
+        Some((footprint, Profile::of_mesh(&mesh, &footprint)?))
+    }
+
+    const CUBE: &str = "4x Cube\" 0 0 1 0 0 3  0 0 1 1 1";
+
+    #[test]
+    fn ramps_turn_to_face_higher_ground() {
+        for angle in 0..4 {
+            let ramp = format!("4x Ramp\" 2 0 1 {angle} 0 3  0 0 1 1 1");
+            let (bricks, report) = build(&[CUBE, &ramp], false);
+            let (asset, brick) = &bricks[1];
+            let (footprint, profile) = shape(asset, brick).unwrap();
+
+            assert_eq!(footprint.min, (-20, 20), "{angle}");
+            assert_eq!(profile.side, Side::Top, "{angle}");
+            // High along the side touching the cube
+            assert_eq!(profile.full, [true, true, false, false], "{angle}");
+            assert!(report.refitted <= 1);
+        }
+    }
+
+    #[test]
+    fn inverted_pieces_fit_by_their_bottoms() {
+        for angle in 0..4 {
+            let ramp = format!("4x Ramp Inv.\" 2 0 1 {angle} 0 3  0 0 1 1 1");
+            let (bricks, _) = build(&[CUBE, &ramp], false);
+            let (asset, brick) = &bricks[1];
+            let (_, profile) = shape(asset, brick).unwrap();
+
+            assert_eq!(profile.side, Side::Bottom, "{angle}");
+            assert_eq!(profile.full, [true, true, false, false], "{angle}");
+        }
+    }
+
+    #[test]
+    fn corners_fill_bends() {
+        let (bricks, report) = build(
+            &[
+                CUBE,
+                "4x Cube\" 0 2 1 0 0 3  0 0 1 1 1",
+                "4x Cube\" 2 0 1 0 0 3  0 0 1 1 1",
+                "4x Ramp\" 2 2 1 0 0 3  0 0 1 1 1",
+            ],
+            false,
+        );
+        let (asset, brick) = &bricks[3];
+        let (_, profile) = shape(asset, brick).unwrap();
+
+        assert_eq!(asset, "PB_DefaultMicroWedgeInnerCorner");
+        // Low only at the corner away from the cubes
+        assert_eq!(profile.full, [true, true, false, true]);
+        assert_eq!(report.refitted, 1);
+    }
+
+    #[test]
+    fn upright_wedges_are_not_slopes() {
+        for angle in 0..4 {
+            let wedge = format!("4x Wedge\" 2 0 1 {angle} 0 3  0 0 1 1 1");
+            let (bricks, report) = build(&[CUBE, &wedge], false);
+            let (asset, brick) = &bricks[1];
+
+            assert_eq!(asset, "PB_DefaultMicroWedge");
+            assert_eq!(brick.direction, ZPositive);
+            assert!(shape(asset, brick).is_none());
+            assert_eq!(report.refitted, 0);
+        }
+    }
+
+    #[test]
+    fn runs_merge_along_their_slopes() {
+        let ramps: Vec<_> = (0..3)
+            .map(|i| format!("4x Ramp\" {} 0 1 1 0 3  0 0 1 1 1", 2 * i))
+            .collect();
+        let lines: Vec<_> = ramps.iter().map(String::as_str).collect();
+
+        let (single, _) = build(&lines[..1], false);
+        let (_, single) = shape(&single[0].0, &single[0].1).unwrap();
+        let (bricks, report) = build(&lines, true);
+        assert_eq!(bricks.len(), 1);
+        assert_eq!(report, TerrainReport { refitted: 0, merged: 2 });
+
+        let (asset, brick) = &bricks[0];
+        let (footprint, profile) = shape(asset, brick).unwrap();
+        assert_eq!((footprint.min, footprint.max), ((-20, -20), (20, 100)));
+        assert_eq!(profile, single);
+    }
+
+    #[test]
+    fn runs_up_a_slope_stay_apart() {
+        let (bricks, report) = build(
+            &[
+                "4x Ramp\" 0 0 1 0 0 3  0 0 1 1 1",
+                "4x Ramp\" 2 0 1 0 0 3  0 0 1 1 1",
+            ],
+            true,
+        );
+        assert_eq!(bricks.len(), 2);
+        assert_eq!(report.merged, 0);
+    }
+}
@@ -0,0 +1,40 @@
+//! Small Blockland saves written out in tests.
+
+/// The text of a save with a plain colorset, holding these brick lines.
+/// Brick lines are like `2x4" 0 0 0.3 0 0 3  0 0 1 1 1`, and extra lines
+/// starting with `+-` belong to the brick before them.
+pub fn bls(lines: &[&str]) -> Vec<u8> {
+    let mut save = String::from("This is a Blockland save file.\r\n1\r\nTest save\r\n");
+    for i in 0..64 {
+        let shade = i as f32 / 63.0;
+        save.push_str(&format!("{:.6} {:.6} {:.6} 1.000000\r\n", shade, 1.0 - shade, 0.5));
+    }
+
+    let count = lines.iter().filter(|line| !line.starts_with("+-")).count();
+    save.push_str(&format!("Linecount {}\r\n", count));
+    for line in lines {
+        save.push_str(line);
+        save.push_str("\r\n");
+    }
+    save.into_bytes()
+}
+
+/// Converts a save made by [`bls`].
+pub fn convert(lines: &[&str], options: &crate::ConvertOptions) -> crate::ConvertReport {
+    let save = bls(lines);
+    crate::convert_with_options(bl_save::Reader::new(&save[..]).unwrap(), options).unwrap()
+}
+
+/// Reads back the bricks of a save made by [`bls`].
+pub fn bricks(lines: &[&str]) -> Vec<bl_save::Brick> {
+    let save = bls(lines);
+    bl_save::Reader::new(&save[..])
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+/// Reads back the only brick of a save made by [`bls`].
+pub fn brick(lines: &[&str]) -> bl_save::Brick {
+    bricks(lines).remove(0)
+}
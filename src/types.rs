@@ -1,3 +1,8 @@
+use crate::{
+    octo::{Octo, OctoStyle},
+    roads::RoadPaint,
+};
+
 pub type BrickMapping = Vec<BrickDesc>;
 
 #[derive(Debug, Clone)]
@@ -15,6 +20,15 @@ pub struct BrickDesc {
     pub modter: bool,
     pub rotate_by_direction: bool,
     pub nocollide: bool,
+    /// The brick is a baseplate, converted by [`BaseplateOptions`](crate::baseplates::BaseplateOptions).
+    pub baseplate: bool,
+    /// Colors the brick from the road palette, overriding `color_override`.
+    pub paint: Option<RoadPaint>,
+    /// Builds an octo centered on `offset` instead of the asset, in the
+    /// [`OctoStyle`] the conversion asks for.
+    pub octo: Option<Octo>,
+    /// Only keeps the brick when converting octos in this style.
+    pub octo_style: Option<OctoStyle>,
 }
 
 impl BrickDesc {
@@ -33,6 +47,10 @@ impl BrickDesc {
             modter: false,
             rotate_by_direction: false,
             nocollide: false,
+            baseplate: false,
+            paint: None,
+            octo: None,
+            octo_style: None,
         }
     }
 
@@ -95,6 +113,26 @@ impl BrickDesc {
         self.nocollide = true;
         self
     }
+
+    pub fn baseplate(mut self) -> Self {
+        self.baseplate = true;
+        self
+    }
+
+    pub fn paint(mut self, paint: RoadPaint) -> Self {
+        self.paint = Some(paint);
+        self
+    }
+
+    pub fn octo(mut self, octo: Octo) -> Self {
+        self.octo = Some(octo);
+        self
+    }
+
+    pub fn octo_style(mut self, style: OctoStyle) -> Self {
+        self.octo_style = Some(style);
+        self
+    }
 }
 
 impl From<BrickDesc> for BrickMapping {
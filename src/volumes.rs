@@ -0,0 +1,168 @@
+//! Water and zone bricks, which stand for a region of space rather than
+//! something solid.
+
+use crate::map_position;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+lazy_static! {
+    /// Dimensions like `32x32`, `8x8x4` or `4x4F`.
+    static ref DIMENSIONS: Regex = Regex::new(r"\b(\d+)x(\d+)(?:x(\d+)|([Ff]))?\b").unwrap();
+
+    /// Sizes of volume bricks that don't name them, in studs and plates.
+    static ref VOLUME_SIZES: HashMap<&'static str, (u32, u32, u32)> = vec![
+        ("Water Brick", (1, 1, 3)),
+        ("Water Plate", (1, 1, 1)),
+        ("Zone Brick", (1, 1, 3)),
+        ("Physical Zone Brick", (1, 1, 3)),
+    ].into_iter().collect();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeKind {
+    Water,
+    /// An event zone, like the ones the zone events add-ons use.
+    Zone,
+    /// A region that changes gravity or pushes players.
+    PhysicalZone,
+}
+
+impl VolumeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            VolumeKind::Water => "water",
+            VolumeKind::Zone => "zone",
+            VolumeKind::PhysicalZone => "physical_zone",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volume {
+    pub kind: VolumeKind,
+    /// Index of the Blockland brick.
+    pub source: usize,
+    /// Corner of the volume with the lowest coordinates, in Brickadia units.
+    pub min: (i32, i32, i32),
+    /// Corner of the volume with the highest coordinates, in Brickadia units.
+    pub max: (i32, i32, i32),
+}
+
+/// Which kind of volume a brick is and its size in studs and plates,
+/// by its ui_name, like `32x32 Water` or `Zone 8x8x8`.
+pub(crate) fn volume_size(ui_name: &str) -> Option<(VolumeKind, (u32, u32, u32))> {
+    let (name, size) = match DIMENSIONS.captures(ui_name) {
+        Some(captures) => {
+            let width = captures.get(1).unwrap().as_str().parse().ok()?;
+            let length = captures.get(2).unwrap().as_str().parse().ok()?;
+            let plates = match (captures.get(3), captures.get(4)) {
+                (Some(height), _) => height.as_str().parse::<u32>().ok()? * 3,
+                (None, Some(_)) => 1,
+                (None, None) => 3,
+            };
+            let whole = captures.get(0).unwrap();
+            let name = format!("{}{}", &ui_name[..whole.start()], &ui_name[whole.end()..]);
+            (name.trim().to_string(), (width, length, plates))
+        }
+        None => (ui_name.to_string(), *VOLUME_SIZES.get(ui_name)?),
+    };
+
+    let kind = match name.trim_end_matches(" Brick").trim_end_matches(" Plate") {
+        "Water" => VolumeKind::Water,
+        "Zone" => VolumeKind::Zone,
+        "Physical Zone" => VolumeKind::PhysicalZone,
+        _ => return None,
+    };
+
+    if size.0 == 0 || size.1 == 0 || size.2 == 0 {
+        return None;
+    }
+
+    Some((kind, size))
+}
+
+/// Reads the volume a brick stands for, if it's a water or zone brick.
+pub(crate) fn brick_volume(source: usize, brick: &bl_save::Brick) -> Option<Volume> {
+    let (kind, (width, length, plates)) = volume_size(&brick.base.ui_name)?;
+
+    // Blockland x and y are swapped in Brickadia, and turned bricks swap them back
+    let (x, y) = if brick.base.angle % 2 == 0 {
+        (length * 5, width * 5)
+    } else {
+        (width * 5, length * 5)
+    };
+    let extents = (x as i32, y as i32, plates as i32 * 2);
+
+    let center = map_position(brick.base.position);
+    Some(Volume {
+        kind,
+        source,
+        min: (
+            center.0 - extents.0,
+            center.1 - extents.1,
+            center.2 - extents.2,
+        ),
+        max: (
+            center.0 + extents.0,
+            center.1 + extents.1,
+            center.2 + extents.2,
+        ),
+    })
+}
+
+impl Volume {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.name(),
+            "source": self.source,
+            "min": self.min,
+            "max": self.max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{brick, convert};
+
+    #[test]
+    fn sizes_volumes_by_ui_name() {
+        assert_eq!(volume_size("32x32 Water"), Some((VolumeKind::Water, (32, 32, 3))));
+        assert_eq!(volume_size("4x8F Water"), Some((VolumeKind::Water, (4, 8, 1))));
+        assert_eq!(volume_size("Zone 8x8x4"), Some((VolumeKind::Zone, (8, 8, 12))));
+        assert_eq!(volume_size("Water Plate"), Some((VolumeKind::Water, (1, 1, 1))));
+        assert_eq!(
+            volume_size("Physical Zone Brick"),
+            Some((VolumeKind::PhysicalZone, (1, 1, 3)))
+        );
+        assert_eq!(volume_size("Zone 0x8"), None);
+        assert_eq!(volume_size("1x1 Water Tap"), None);
+    }
+
+    #[test]
+    fn places_turned_volumes() {
+        let volume = brick_volume(3, &brick(&["2x4 Water\" 1 0 0.3 1 0 3  0 0 1 1 1"])).unwrap();
+        assert_eq!(volume.source, 3);
+        assert_eq!(volume.min, (-10, 0, 0));
+        assert_eq!(volume.max, (10, 40, 12));
+    }
+
+    #[test]
+    fn leaves_volumes_out_of_the_save() {
+        let report = convert(
+            &[
+                "32x32 Water\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "Zone Brick\" 0 0 0.3 0 0 3  0 0 1 1 1",
+                "Water Bottle\" 0 0 0.3 0 0 3  0 0 1 1 1",
+            ],
+            &Default::default(),
+        );
+        assert_eq!(report.volumes.len(), 2);
+        assert!(report.write_data.bricks.is_empty());
+        // Not a volume, so it's left to the other mappings
+        assert_eq!(report.unknown_ui_names["Water Bottle"], 1);
+    }
+}
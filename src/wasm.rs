@@ -0,0 +1,121 @@
+//! Bindings for using the converter from JavaScript, built with the `wasm`
+//! feature for `wasm32-unknown-unknown`.
+
+use crate::{convert_with_options, ConvertOptions};
+use brs::chrono::{TimeZone, Utc};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+/// A converted save and its report.
+#[wasm_bindgen]
+pub struct Conversion {
+    brs: Vec<u8>,
+    report: String,
+}
+
+#[wasm_bindgen]
+impl Conversion {
+    /// The save in brs format.
+    #[wasm_bindgen(getter)]
+    pub fn brs(&self) -> Vec<u8> {
+        self.brs.clone()
+    }
+
+    /// The report as JSON, see `ConvertReport::to_json`.
+    #[wasm_bindgen(getter)]
+    pub fn report(&self) -> String {
+        self.report.clone()
+    }
+}
+
+/// Converts the contents of a bls file.
+///
+/// `options` is a JSON object with these optional fields, or an empty string:
+/// - `music`: Brickadia audio assets by Blockland music loop datablock.
+/// - `vehicles`: Brickadia vehicle prefabs by Blockland vehicle datablock.
+/// - `save_time`: When the save was made, in milliseconds since the Unix epoch.
+///   Pass `Date.now()` to avoid depending on a clock inside WebAssembly.
+#[wasm_bindgen(js_name = convertBls)]
+pub fn convert_bls(bls: &[u8], options: &str) -> Result<Conversion, JsValue> {
+    let options = parse_options(options)?;
+
+    let reader = bl_save::Reader::new(bls).map_err(to_js_error)?;
+    let report = convert_with_options(reader, &options).map_err(to_js_error)?;
+
+    let mut brs = Vec::new();
+    brs::write_save(&mut brs, &report.write_data).map_err(to_js_error)?;
+
+    Ok(Conversion {
+        brs,
+        report: report.to_json().to_string(),
+    })
+}
+
+fn parse_options(options: &str) -> Result<ConvertOptions, JsValue> {
+    let mut parsed = ConvertOptions::default();
+
+    if options.trim().is_empty() {
+        return Ok(parsed);
+    }
+
+    let options: Value = serde_json::from_str(options).map_err(to_js_error)?;
+
+    if let Some(music) = options.get("music").and_then(Value::as_object) {
+        for (music, asset) in music {
+            let asset = asset
+                .as_str()
+                .ok_or_else(|| JsValue::from_str("music assets must be strings"))?;
+            parsed.music.insert(music.clone(), asset.to_string());
+        }
+    }
+
+    if let Some(vehicles) = options.get("vehicles").and_then(Value::as_object) {
+        for (vehicle, prefab) in vehicles {
+            let prefab = prefab
+                .as_str()
+                .ok_or_else(|| JsValue::from_str("vehicle prefabs must be strings"))?;
+            parsed.vehicles.insert(vehicle.clone(), prefab.to_string());
+        }
+    }
+
+    if let Some(save_time) = options.get("save_time").and_then(Value::as_f64) {
+        parsed.save_time = Utc.timestamp_millis_opt(save_time as i64).single();
+    }
+
+    Ok(parsed)
+}
+
+fn to_js_error(error: impl std::fmt::Display) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Errors become JavaScript values, which only exist in WebAssembly, so
+    // only options that parse are checked here.
+
+    #[test]
+    fn empty_options_are_the_defaults() {
+        let options = parse_options(" ").unwrap();
+        assert!(options.music.is_empty());
+        assert!(options.vehicles.is_empty());
+        assert_eq!(options.save_time, None);
+    }
+
+    #[test]
+    fn parses_tables_and_save_time() {
+        let options = parse_options(
+            r#"{
+                "music": { "musicData_Drone": "BA_AMB_Drone" },
+                "vehicles": { "Jeep": "BV_Jeep" },
+                "save_time": 1000
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(options.music["musicData_Drone"], "BA_AMB_Drone");
+        assert_eq!(options.vehicles["Jeep"], "BV_Jeep");
+        assert_eq!(options.save_time, Utc.timestamp_millis_opt(1000).single());
+    }
+}